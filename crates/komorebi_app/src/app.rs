@@ -1,23 +1,127 @@
 use std::{
+    any::{Any, TypeId},
     collections::HashSet,
+    fmt,
+    num::NonZeroU8,
     panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 // use crate::window;
-use crate::{Plugin, Plugins};
+use crate::{
+    crash_reports, run_fixed_update_schedule, AppLabel, First, FixedTime, Main, MainScheduleOrder,
+    Plugin, Plugins, RunFixedUpdateLoop, RunMain, RunningPlugin, SubApp,
+};
 
-use komorebi_utils::tracing::debug;
+use komorebi_ecs::{
+    Event, Events, IntoSystemConfigs, IntoSystemSetConfigs, ManualEventReader, Resource, Schedule,
+    ScheduleLabel, Schedules, World,
+};
+use komorebi_utils::tracing::{debug, error};
 
-pub(crate) enum AppError {
+/// Error returned when plugins cannot be added to an [`App`]
+#[derive(Debug)]
+pub enum AppError {
+    /// A unique plugin was added twice
     DuplicatePlugin { plugin_name: String },
+    /// A plugin was added before one of its [dependencies](Plugin::dependencies)
+    MissingDependency {
+        plugin_name: String,
+        dependency_name: &'static str,
+    },
+    /// A plugin was added once plugins were [finished](App::finish)
+    PluginsFinished { plugin_name: String },
+    /// [`Plugin::try_build`] failed
+    PluginBuild {
+        plugin_name: String,
+        source: BoxedError,
+    },
+    /// Plugins of a group [sorted by dependencies](crate::PluginGroupBuilder::sort_by_dependencies)
+    /// depend on each other in a cycle
+    DependencyCycle { plugin_names: Vec<String> },
+    /// Plugins were not [ready](Plugin::ready) before the timeout of [`App::wait_for_plugins`]
+    PluginsNotReady {
+        plugin_names: Vec<String>,
+        timeout: Duration,
+    },
+    /// Adding a plugin group failed
+    InGroup {
+        group_name: String,
+        source: Box<AppError>,
+    },
+}
+
+/// Error reported by a plugin, see [`Plugin::try_build`]
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::DuplicatePlugin { plugin_name } => {
+                write!(f, "Duplicate plugin: {plugin_name}")
+            }
+            AppError::MissingDependency {
+                plugin_name,
+                dependency_name,
+            } => write!(
+                f,
+                "plugin {plugin_name} requires plugin {dependency_name}, which must be added before it"
+            ),
+            AppError::PluginsFinished { plugin_name } => write!(
+                f,
+                "plugin {plugin_name} cannot be added once plugins are finished"
+            ),
+            AppError::PluginBuild {
+                plugin_name,
+                source,
+            } => write!(f, "plugin {plugin_name} failed to build: {source}"),
+            AppError::DependencyCycle { plugin_names } => write!(
+                f,
+                "plugins depend on each other in a cycle: {}",
+                plugin_names.join(", ")
+            ),
+            AppError::PluginsNotReady {
+                plugin_names,
+                timeout,
+            } => write!(
+                f,
+                "plugins still not ready after {timeout:?}: {}",
+                plugin_names.join(", ")
+            ),
+            AppError::InGroup { group_name, source } => {
+                write!(f, "Error adding plugin in group {group_name}: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::PluginBuild { source, .. } => Some(&**source),
+            AppError::InGroup { source, .. } => Some(&**source),
+            _ => None,
+        }
+    }
 }
 
 pub struct App {
     // pub window: window::Window,
-    plugin_registry: Vec<Box<dyn Plugin>>,
+    pub world: World,
+    plugin_registry: Vec<Rc<dyn Plugin>>,
     plugin_name_added: HashSet<String>,
+    plugin_types_added: HashSet<TypeId>,
     /// prevent incorrect calls to `App::run()` from `Plugin::build()`
     building_plugin_depth: usize,
+    /// Whether a plugin panicked while built, reported by the
+    /// [`PanicHandlerPlugin`](crate::PanicHandlerPlugin)
+    build_panicked: bool,
+    plugins_state: PluginsState,
+    runner: Box<dyn FnOnce(App) -> AppExit>,
+    main_schedule_label: Arc<dyn ScheduleLabel>,
+    pub(crate) sub_apps: Vec<(Arc<dyn AppLabel>, SubApp)>,
 }
 
 // Dummy plugin used to temporary hold the place in the plugin registry
@@ -28,49 +132,530 @@ impl Plugin for PlaceholderPlugin {
 
 impl App {
     pub fn new() -> App {
+        let mut app = App::empty();
+        app.world.init_resource::<MainScheduleOrder>();
+        app.world.init_resource::<FixedTime>();
+        app.add_event::<AppExit>()
+            .add_systems(Main, RunMain::new())
+            .add_systems(RunFixedUpdateLoop, run_fixed_update_schedule);
+        app
+    }
+
+    /// Creates an app without any schedule, resource or plugin, running [`run_once`]
+    pub fn empty() -> App {
+        let mut world = World::new();
+        world.init_resource::<Schedules>();
         App {
             // window: window::Window::new(),
+            world,
             plugin_registry: Default::default(),
             plugin_name_added: Default::default(),
+            plugin_types_added: Default::default(),
             building_plugin_depth: 0,
+            build_panicked: false,
+            plugins_state: PluginsState::Adding,
+            runner: Box::new(run_once),
+            main_schedule_label: Arc::new(Main),
+            sub_apps: Vec::new(),
+        }
+    }
+
+    /// Runs the main schedule once, then extracts and updates each [`SubApp`]
+    pub fn update(&mut self) {
+        self.world.run_schedule_ref(&*self.main_schedule_label);
+        for (_label, sub_app) in &mut self.sub_apps {
+            #[cfg(feature = "trace")]
+            let _span = komorebi_utils::tracing::info_span!("sub app", name = ?_label).entered();
+            sub_app.extract(&mut self.world);
+            sub_app.run();
         }
     }
 
-    pub fn run(&self) {
-        println!("hello komorebi");
-        // self.window.run();
+    /// Runs [`App::update`] `n` times, to drive the app by hand like in tests
+    ///
+    /// Plugins are first waited on, [finished](App::finish) and [cleaned up](App::cleanup), as
+    /// [`App::run`] would.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugins are not ready within [`App::PLUGINS_READY_TIMEOUT`].
+    pub fn step_n(&mut self, n: usize) -> &mut Self {
+        if let Err(e) = self.wait_for_plugins(Self::PLUGINS_READY_TIMEOUT) {
+            panic!("{e}");
+        }
+        for _ in 0..n {
+            self.update();
+        }
+        self
+    }
+
+    /// Sends an event, like input that a test injects between updates
+    pub fn send_event<E: Event>(&mut self, event: E) -> &mut Self {
+        self.world.send_event(event);
+        self
+    }
+
+    /// Sets the schedule run by [`App::update`], [`Main`] by default
+    pub fn set_main_schedule_label(&mut self, label: impl ScheduleLabel) -> &mut Self {
+        self.main_schedule_label = Arc::new(label);
+        self
+    }
+
+    /// Hands the app over to its runner, which drives [`App::update`], and returns how it exited
+    ///
+    /// Plugins are first waited on until [ready](Plugin::ready), then [finished](App::finish)
+    /// and [cleaned up](App::cleanup), unless that was done by hand. If they are not ready
+    /// within [`App::PLUGINS_READY_TIMEOUT`], the error is logged and the runner is not called.
+    /// On the web, where waiting would block the page, runners finish the plugins once ready
+    /// with [`App::finish_plugins_if_ready`] instead. The default runner calls
+    /// [`App::update`] once; plugins such as the windowing plugin install their own main loop
+    /// with [`App::set_runner`], which stops once an [`AppExit`] event is sent.
+    ///
+    /// With a [`PanicHandlerPlugin`](crate::PanicHandlerPlugin), a panic in the runner unwinds
+    /// to here, dropping the app on the way so that its logs and traces are flushed, and is
+    /// returned as [`AppExit::PANIC`], as is a panic that happened while building a plugin.
+    /// Without one, the panic is resumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from [`Plugin::build`].
+    pub fn run(&mut self) -> AppExit {
+        if self.building_plugin_depth > 0 {
+            panic!("App::run() was called from within Plugin::build(), which is not allowed");
+        }
+        let mut app = std::mem::replace(self, App::empty());
+        if app.build_panicked {
+            return AppExit::PANIC;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(e) = app.wait_for_plugins(Self::PLUGINS_READY_TIMEOUT) {
+            error!("{e}");
+            return AppExit::error();
+        }
+        #[cfg(target_arch = "wasm32")]
+        app.finish_plugins_if_ready();
+        let runner = std::mem::replace(&mut app.runner, Box::new(run_once));
+        let Some(crash_reports) = crash_reports(&app) else {
+            return runner(app);
+        };
+        match catch_unwind(AssertUnwindSafe(|| runner(app))) {
+            Ok(exit) => exit,
+            Err(payload) => {
+                crash_reports.report(&*payload);
+                AppExit::PANIC
+            }
+        }
+    }
+
+    /// How long [`App::run`] and [`App::step_n`] wait for the plugins to be ready
+    pub const PLUGINS_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// [Finishes](App::finish) and [cleans up](App::cleanup) the plugins once they are all
+    /// [ready](Plugin::ready), unless that was done by hand
+    ///
+    /// Returns whether the plugins are cleaned up, so that runners which cannot block can call
+    /// this on every iteration of their loop, and update the app once it returns `true`.
+    pub fn finish_plugins_if_ready(&mut self) -> bool {
+        if self.plugins_state() == PluginsState::Ready {
+            self.finish();
+        }
+        if self.plugins_state() == PluginsState::Finished {
+            self.cleanup();
+        }
+        self.plugins_state() == PluginsState::Cleaned
+    }
+
+    /// Blocks until the plugins are ready, then finishes and cleans them up, see
+    /// [`App::finish_plugins_if_ready`]
+    ///
+    /// Readiness is polled with a growing sleep, up to 10 ms, so that waiting does not take
+    /// up a core. Fails with [`AppError::PluginsNotReady`] once `timeout` elapsed.
+    pub fn wait_for_plugins(&mut self, timeout: Duration) -> Result<(), AppError> {
+        #[cfg(feature = "trace")]
+        let _span = komorebi_utils::tracing::info_span!("plugins ready").entered();
+        let start = Instant::now();
+        let mut sleep = Duration::from_micros(100);
+        while !self.finish_plugins_if_ready() {
+            if start.elapsed() >= timeout {
+                return Err(AppError::PluginsNotReady {
+                    plugin_names: self
+                        .plugin_registry
+                        .iter()
+                        .filter(|plugin| !plugin.ready(self))
+                        .map(|plugin| plugin.name().to_string())
+                        .collect(),
+                    timeout,
+                });
+            }
+            std::thread::sleep(sleep.min(timeout.saturating_sub(start.elapsed())));
+            sleep = (sleep * 2).min(Duration::from_millis(10));
+        }
+        Ok(())
+    }
+
+    /// The exit requested by the [`AppExit`] events of the last two updates, if any
+    ///
+    /// Errors take precedence over [`AppExit::Success`]. Runners call this after each update.
+    pub fn should_exit(&self) -> Option<AppExit> {
+        let events = self.world.get_resource::<Events<AppExit>>()?;
+        let mut reader = ManualEventReader::default();
+        let mut exit = None;
+        for event in reader.read(&events) {
+            if event.is_error() {
+                return Some(*event);
+            }
+            exit = Some(*event);
+        }
+        exit
+    }
+
+    /// How far the registered plugins are through their lifecycle
+    pub fn plugins_state(&self) -> PluginsState {
+        match self.plugins_state {
+            PluginsState::Adding if self.plugin_registry.iter().all(|p| p.ready(self)) => {
+                PluginsState::Ready
+            }
+            state => state,
+        }
+    }
+
+    /// Calls [`Plugin::finish`] on every plugin, in the order they were added
+    ///
+    /// # Panics
+    ///
+    /// Panics if plugins were already finished.
+    pub fn finish(&mut self) {
+        assert!(
+            matches!(
+                self.plugins_state,
+                PluginsState::Adding | PluginsState::Ready
+            ),
+            "plugins were already finished"
+        );
+        // Set first, so that plugins cannot be added from `Plugin::finish`
+        self.plugins_state = PluginsState::Finished;
+        // Plugins cannot be added anymore, and stay listed while they run
+        for index in 0..self.plugin_registry.len() {
+            let plugin = Rc::clone(&self.plugin_registry[index]);
+            #[cfg(feature = "trace")]
+            let _span =
+                komorebi_utils::tracing::info_span!("plugin finish", plugin = plugin.name())
+                    .entered();
+            let _running = RunningPlugin::enter(plugin.name());
+            plugin.finish(self);
+        }
+        for (_, sub_app) in &mut self.sub_apps {
+            if sub_app.app.plugins_state != PluginsState::Finished {
+                sub_app.app.finish();
+            }
+        }
+    }
+
+    /// Calls [`Plugin::cleanup`] on every plugin, in the order they were added
+    ///
+    /// # Panics
+    ///
+    /// Panics if plugins were not finished, or already cleaned up.
+    pub fn cleanup(&mut self) {
+        assert_eq!(
+            self.plugins_state,
+            PluginsState::Finished,
+            "plugins must be finished, and not cleaned up yet"
+        );
+        self.plugins_state = PluginsState::Cleaned;
+        // Plugins cannot be added anymore, and stay listed while they run
+        for index in 0..self.plugin_registry.len() {
+            let plugin = Rc::clone(&self.plugin_registry[index]);
+            #[cfg(feature = "trace")]
+            let _span =
+                komorebi_utils::tracing::info_span!("plugin cleanup", plugin = plugin.name())
+                    .entered();
+            let _running = RunningPlugin::enter(plugin.name());
+            plugin.cleanup(self);
+        }
+        for (_, sub_app) in &mut self.sub_apps {
+            if sub_app.app.plugins_state == PluginsState::Finished {
+                sub_app.app.cleanup();
+            }
+        }
+    }
+
+    /// Sets the function that [`App::run`] hands the app over to
+    pub fn set_runner(&mut self, runner: impl FnOnce(App) -> AppExit + 'static) -> &mut Self {
+        self.runner = Box::new(runner);
+        self
     }
 
     pub(crate) fn add_boxed_plugin(
         &mut self,
         plugin: Box<dyn Plugin>,
     ) -> Result<&mut Self, AppError> {
+        if self.build_panicked {
+            debug!("not adding plugin {} after a panic", plugin.name());
+            return Ok(self);
+        }
         debug!("added plugin: {}", plugin.name());
+        if !matches!(
+            self.plugins_state,
+            PluginsState::Adding | PluginsState::Ready
+        ) {
+            Err(AppError::PluginsFinished {
+                plugin_name: plugin.name().to_string(),
+            })?;
+        }
+        if let Some(dependency) = plugin
+            .dependencies()
+            .into_iter()
+            .find(|dependency| !self.plugin_types_added.contains(&dependency.type_id()))
+        {
+            Err(AppError::MissingDependency {
+                plugin_name: plugin.name().to_string(),
+                dependency_name: dependency.name(),
+            })?;
+        }
         if plugin.is_unique() && !self.plugin_name_added.insert(plugin.name().to_string()) {
             Err(AppError::DuplicatePlugin {
                 plugin_name: plugin.name().to_string(),
             })?;
         }
+        let plugin_type = Any::type_id(plugin.as_any());
+        let newly_added_type = self.plugin_types_added.insert(plugin_type);
 
         let plugin_pos_in_registry = self.plugin_registry.len();
-        self.plugin_registry.push(Box::new(PlaceholderPlugin));
+        self.plugin_registry.push(Rc::new(PlaceholderPlugin));
 
         self.building_plugin_depth += 1;
-        let result = catch_unwind(AssertUnwindSafe(|| plugin.build(self)));
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            #[cfg(feature = "trace")]
+            let _span = komorebi_utils::tracing::info_span!("plugin build", plugin = plugin.name())
+                .entered();
+            let _running = RunningPlugin::enter(plugin.name());
+            plugin.try_build(self)
+        }));
         self.building_plugin_depth -= 1;
-        if let Err(payload) = result {
-            resume_unwind(payload);
+        match result {
+            Ok(Ok(())) => {
+                self.plugin_registry[plugin_pos_in_registry] = plugin.into();
+                Ok(self)
+            }
+            Ok(Err(source)) => {
+                // Forget the plugin, so that it can be added again once fixed
+                self.plugin_registry.remove(plugin_pos_in_registry);
+                if plugin.is_unique() {
+                    self.plugin_name_added.remove(plugin.name());
+                }
+                if newly_added_type {
+                    self.plugin_types_added.remove(&plugin_type);
+                }
+                Err(AppError::PluginBuild {
+                    plugin_name: plugin.name().to_string(),
+                    source,
+                })
+            }
+            Err(payload) => match crash_reports(self) {
+                // Reported once, by the outermost plugin being built
+                Some(crash_reports) if self.building_plugin_depth == 0 => {
+                    crash_reports.report(&*payload);
+                    self.plugin_registry.remove(plugin_pos_in_registry);
+                    self.build_panicked = true;
+                    Ok(self)
+                }
+                _ => resume_unwind(payload),
+            },
         }
-        self.plugin_registry[plugin_pos_in_registry] = plugin;
-        Ok(self)
     }
 
+    /// Whether a plugin of type `T` was added, including one still being built
+    pub fn is_plugin_added<T: Plugin>(&self) -> bool {
+        self.plugin_types_added.contains(&TypeId::of::<T>())
+    }
+
+    /// The built plugins, skipping the placeholders of those still being built
+    fn built_plugins(&self) -> impl Iterator<Item = &Rc<dyn Plugin>> {
+        self.plugin_registry
+            .iter()
+            .filter(|plugin| plugin.downcast_ref::<PlaceholderPlugin>().is_none())
+    }
+
+    /// The built plugins of type `T`, in the order they were added
+    pub fn get_added_plugins<T: Plugin>(&self) -> Vec<&T> {
+        self.built_plugins()
+            .filter_map(|plugin| plugin.downcast_ref::<T>())
+            .collect()
+    }
+
+    /// Names of the built plugins, in the order they were added
+    pub fn plugin_names(&self) -> impl Iterator<Item = &str> {
+        self.built_plugins().map(|plugin| plugin.name())
+    }
+
+    /// Adds and builds plugins or plugin groups
+    ///
+    /// # Panics
+    ///
+    /// Panics if adding any of the plugins fails, see [`App::try_add_plugins`].
     pub fn add_plugins<M>(&mut self, plugins: impl Plugins<M>) -> &mut Self {
-        plugins.add_to_app(self);
+        if let Err(e) = plugins.try_add_to_app(self) {
+            panic!("{e}");
+        }
+        self
+    }
+
+    /// Adds and builds plugins or plugin groups, stopping at the first failure
+    ///
+    /// Plugins of a group added before the failing one stay added.
+    pub fn try_add_plugins<M>(&mut self, plugins: impl Plugins<M>) -> Result<&mut Self, AppError> {
+        plugins.try_add_to_app(self)?;
+        Ok(self)
+    }
+
+    /// Adds systems to the schedule `label`, creating the schedule if needed
+    pub fn add_systems<M>(
+        &mut self,
+        label: impl ScheduleLabel,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self {
+        self.edit_schedule(label, |schedule| {
+            schedule.add_systems(systems);
+        })
+    }
+
+    /// Configures system sets in the schedule with the given label, creating it if needed
+    pub fn configure_sets<M>(
+        &mut self,
+        label: impl ScheduleLabel,
+        sets: impl IntoSystemSetConfigs<M>,
+    ) -> &mut Self {
+        self.edit_schedule(label, |schedule| {
+            schedule.configure_sets(sets);
+        })
+    }
+
+    /// Adds a schedule, replacing any schedule with the same label
+    pub fn add_schedule(&mut self, schedule: Schedule) -> &mut Self {
+        self.world.resource_mut::<Schedules>().insert(schedule);
+        self
+    }
+
+    /// Creates an empty schedule `label` if it does not exist yet
+    pub fn init_schedule(&mut self, label: impl ScheduleLabel) -> &mut Self {
+        let mut schedules = self.world.resource_mut::<Schedules>();
+        if !schedules.contains(&label) {
+            schedules.insert(Schedule::new(label));
+        }
+        drop(schedules);
+        self
+    }
+
+    /// Applies `f` to the schedule `label`, creating the schedule if needed
+    pub fn edit_schedule(
+        &mut self,
+        label: impl ScheduleLabel,
+        f: impl FnOnce(&mut Schedule),
+    ) -> &mut Self {
+        let mut schedules = self.world.resource_mut::<Schedules>();
+        match schedules.get_mut(&label) {
+            Some(schedule) => f(schedule),
+            None => {
+                let mut schedule = Schedule::new(label);
+                f(&mut schedule);
+                schedules.insert(schedule);
+            }
+        }
+        drop(schedules);
+        self
+    }
+
+    /// Registers the [`Events`] resource of `E`, updated at the start of every frame
+    pub fn add_event<E: Event>(&mut self) -> &mut Self {
+        if !self.world.contains_resource::<Events<E>>() {
+            self.init_resource::<Events<E>>()
+                .add_systems(First, Events::<E>::update_system);
+        }
+        self
+    }
+
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> &mut Self {
+        self.world.insert_resource(resource);
+        self
+    }
+
+    pub fn init_resource<R: Resource + Default>(&mut self) -> &mut Self {
+        self.world.init_resource::<R>();
         self
     }
 }
 
+/// Lifecycle of the plugins of an [`App`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PluginsState {
+    /// Plugins are being added, and some are not [ready](Plugin::ready)
+    Adding,
+    /// Every plugin is ready to be finished
+    Ready,
+    /// [`App::finish`] was called
+    Finished,
+    /// [`App::cleanup`] was called
+    Cleaned,
+}
+
+/// The default runner, updating the app once
+pub fn run_once(mut app: App) -> AppExit {
+    app.update();
+    app.should_exit().unwrap_or_default()
+}
+
+/// Event asking the runner to stop updating the app, also returned by [`App::run`]
+///
+/// Can be returned from `main` to become the process exit code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AppExit {
+    /// Exit code 0
+    #[default]
+    Success,
+    /// A non-zero exit code
+    Error(NonZeroU8),
+}
+
+impl AppExit {
+    /// Exit code 101, the code of a process exiting on a panic
+    pub const PANIC: Self = Self::from_code(101);
+
+    /// An error with exit code 1
+    pub const fn error() -> Self {
+        Self::Error(NonZeroU8::MIN)
+    }
+
+    /// [`AppExit::Success`] for 0, an error otherwise
+    pub const fn from_code(code: u8) -> Self {
+        match NonZeroU8::new(code) {
+            Some(code) => Self::Error(code),
+            None => Self::Success,
+        }
+    }
+
+    pub const fn code(&self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::Error(code) => code.get(),
+        }
+    }
+
+    pub const fn is_success(&self) -> bool {
+        matches!(self, Self::Success)
+    }
+
+    pub const fn is_error(&self) -> bool {
+        matches!(self, Self::Error(_))
+    }
+}
+
+impl std::process::Termination for AppExit {
+    fn report(self) -> std::process::ExitCode {
+        self.code().into()
+    }
+}
+
 impl Default for App {
     fn default() -> Self {
         Self::new()
@@ -79,6 +664,259 @@ impl Default for App {
 
 #[cfg(test)]
 mod tests {
+    use crate::prelude::*;
+    use crate::PluginsState;
+    use komorebi_ecs::prelude::*;
+
     #[test]
     fn it_works() {}
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    #[test]
+    fn main_schedule_order() {
+        let mut app = App::new();
+        app.init_resource::<Log>()
+            .add_systems(Last, |mut log: ResMut<Log>| log.0.push("last"))
+            .add_systems(Update, |mut log: ResMut<Log>| log.0.push("update"))
+            .add_systems(Startup, |mut log: ResMut<Log>| log.0.push("startup"))
+            .add_systems(First, |mut log: ResMut<Log>| log.0.push("first"));
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world.resource::<Log>().0,
+            ["startup", "first", "update", "last", "first", "update", "last"]
+        );
+    }
+
+    #[test]
+    fn runner() {
+        let mut app = App::new();
+        app.init_resource::<Log>()
+            .add_systems(Update, |mut log: ResMut<Log>| log.0.push("update"))
+            .set_runner(|mut app| {
+                for _ in 0..3 {
+                    app.update();
+                }
+                assert_eq!(app.world.resource::<Log>().0, ["update"; 3]);
+                AppExit::error()
+            });
+        assert_eq!(app.run(), AppExit::Error(1.try_into().unwrap()));
+    }
+
+    #[test]
+    fn exit_code() {
+        let mut app = App::new();
+        assert_eq!(app.should_exit(), None);
+        app.add_systems(Update, |mut exit: EventWriter<AppExit>| {
+            exit.send(AppExit::Success);
+            exit.send(AppExit::from_code(3));
+            exit.send(AppExit::Success);
+        });
+        let exit = app.run();
+        assert_eq!(exit, AppExit::from_code(3));
+        assert_eq!(exit.code(), 3);
+        assert!(exit.is_error());
+        assert_eq!(App::new().run(), AppExit::Success);
+
+        let directory =
+            std::env::temp_dir().join(format!("komorebi_exit_code_{}", std::process::id()));
+        let mut app = App::new();
+        app.add_plugins(PanicHandlerPlugin {
+            directory: directory.clone(),
+            ..Default::default()
+        })
+        .add_systems(Update, || panic!("system panic"));
+        assert_eq!(app.run(), AppExit::PANIC);
+        let report = std::fs::read_dir(&directory).unwrap().next().unwrap();
+        let report = std::fs::read_to_string(report.unwrap().path()).unwrap();
+        let _ = std::fs::remove_dir_all(&directory);
+        assert!(report.contains("System: komorebi_app::app::tests::exit_code::{{closure}}"));
+    }
+
+    #[test]
+    #[should_panic(expected = "runner panic")]
+    fn runner_panic_without_handler() {
+        let mut app = App::new();
+        app.set_runner(|_| panic!("runner panic"));
+        app.run();
+    }
+
+    #[test]
+    #[should_panic(expected = "App::run() was called from within Plugin::build()")]
+    fn run_from_build() {
+        struct RunPlugin;
+        impl Plugin for RunPlugin {
+            fn build(&self, app: &mut App) {
+                app.run();
+            }
+        }
+        App::new().add_plugins(RunPlugin);
+    }
+
+    #[test]
+    fn plugin_lifecycle() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        struct LifecyclePlugin {
+            name: &'static str,
+            ready: Arc<AtomicBool>,
+        }
+        impl Plugin for LifecyclePlugin {
+            fn build(&self, app: &mut App) {
+                app.world.resource_mut::<Log>().0.push(self.name);
+            }
+            fn ready(&self, _app: &App) -> bool {
+                self.ready.load(Ordering::Relaxed)
+            }
+            fn finish(&self, app: &mut App) {
+                app.world.resource_mut::<Log>().0.push("finish");
+            }
+            fn cleanup(&self, app: &mut App) {
+                app.world.resource_mut::<Log>().0.push("cleanup");
+            }
+            fn name(&self) -> &str {
+                self.name
+            }
+        }
+
+        let ready = Arc::new(AtomicBool::new(false));
+        let mut app = App::new();
+        app.init_resource::<Log>()
+            .add_plugins(LifecyclePlugin {
+                name: "a",
+                ready: Arc::new(AtomicBool::new(true)),
+            })
+            .add_plugins(LifecyclePlugin {
+                name: "b",
+                ready: ready.clone(),
+            });
+        assert_eq!(app.plugins_state(), PluginsState::Adding);
+
+        // Becomes ready while `run` polls, as after an asynchronous request
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            ready.store(true, Ordering::Relaxed);
+        });
+        app.set_runner(|app| {
+            assert_eq!(app.plugins_state(), PluginsState::Cleaned);
+            assert_eq!(
+                app.world.resource::<Log>().0,
+                ["a", "b", "finish", "finish", "cleanup", "cleanup"]
+            );
+            AppExit::Success
+        });
+        assert_eq!(app.run(), AppExit::Success);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn plugins_not_ready() {
+        struct NeverReadyPlugin;
+        impl Plugin for NeverReadyPlugin {
+            fn build(&self, _app: &mut App) {}
+            fn ready(&self, _app: &App) -> bool {
+                false
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins(NeverReadyPlugin);
+        let error = app
+            .wait_for_plugins(std::time::Duration::from_millis(20))
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            crate::AppError::PluginsNotReady { plugin_names, .. }
+                if plugin_names == &["komorebi_app::app::tests::plugins_not_ready::NeverReadyPlugin"]
+        ));
+        assert_eq!(app.plugins_state(), PluginsState::Adding);
+        assert!(!app.finish_plugins_if_ready());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be added once plugins are finished")]
+    fn add_plugin_from_finish() {
+        struct LatePlugin;
+        impl Plugin for LatePlugin {
+            fn build(&self, _app: &mut App) {}
+        }
+        struct AddingPlugin;
+        impl Plugin for AddingPlugin {
+            fn build(&self, _app: &mut App) {}
+            fn finish(&self, app: &mut App) {
+                app.add_plugins(LatePlugin);
+            }
+        }
+        App::new().add_plugins(AddingPlugin).finish();
+    }
+
+    #[test]
+    fn plugin_queries() {
+        struct CountPlugin(u32);
+        impl Plugin for CountPlugin {
+            fn build(&self, app: &mut App) {
+                assert!(app.is_plugin_added::<CountPlugin>());
+                assert!(app.get_added_plugins::<CountPlugin>().len() < self.0 as usize);
+            }
+            fn is_unique(&self) -> bool {
+                false
+            }
+        }
+
+        struct OtherPlugin;
+        impl Plugin for OtherPlugin {
+            fn build(&self, _app: &mut App) {}
+            fn name(&self) -> &str {
+                "other"
+            }
+        }
+
+        let mut app = App::new();
+        assert!(!app.is_plugin_added::<CountPlugin>());
+        app.add_plugins(CountPlugin(1))
+            .add_plugins(OtherPlugin)
+            .add_plugins(CountPlugin(2));
+        let counts: Vec<u32> = app
+            .get_added_plugins::<CountPlugin>()
+            .iter()
+            .map(|plugin| plugin.0)
+            .collect();
+        assert_eq!(counts, [1, 2]);
+        assert!(app.is_plugin_added::<OtherPlugin>());
+        assert_eq!(
+            app.plugin_names().collect::<Vec<_>>(),
+            [
+                "komorebi_app::app::tests::plugin_queries::CountPlugin",
+                "other",
+                "komorebi_app::app::tests::plugin_queries::CountPlugin"
+            ]
+        );
+    }
+
+    #[test]
+    fn plugin_names_while_building() {
+        struct NamesPlugin;
+        impl Plugin for NamesPlugin {
+            fn build(&self, app: &mut App) {
+                let names: Vec<String> = app.plugin_names().map(String::from).collect();
+                app.insert_resource(names);
+            }
+        }
+
+        struct OtherPlugin;
+        impl Plugin for OtherPlugin {
+            fn build(&self, _app: &mut App) {}
+            fn name(&self) -> &str {
+                "other"
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins(OtherPlugin).add_plugins(NamesPlugin);
+        // The plugin being built is not listed yet
+        assert_eq!(*app.world.resource::<Vec<String>>(), ["other"]);
+    }
 }
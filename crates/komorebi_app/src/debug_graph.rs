@@ -0,0 +1,118 @@
+use std::{fmt::Write, path::PathBuf};
+
+use komorebi_ecs::{dot_quote, Schedules};
+use komorebi_utils::tracing::{error, info};
+
+use crate::{App, Plugin};
+
+impl App {
+    /// The plugins and schedules of this app as a Graphviz DOT graph
+    ///
+    /// Plugins are listed in the order they were added, each pointing to the next. Every
+    /// schedule is drawn as a cluster, as described in
+    /// [`Schedule::write_dot`](komorebi_ecs::Schedule::write_dot).
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph app {\n    rankdir=LR;\n");
+        self.write_dot(&mut dot)
+            .expect("writing to a String cannot fail");
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "    subgraph cluster_plugins {{")?;
+        writeln!(out, "        label=\"Plugins\";")?;
+        let names: Vec<&str> = self.plugin_names().collect();
+        for (index, name) in names.iter().enumerate() {
+            writeln!(
+                out,
+                "        plugin_{index} [label={}, shape=component];",
+                dot_quote(name)
+            )?;
+        }
+        for index in 1..names.len() {
+            writeln!(out, "        plugin_{} -> plugin_{index};", index - 1)?;
+        }
+        writeln!(out, "    }}")?;
+
+        let schedules = self.world.resource::<Schedules>();
+        let mut schedules: Vec<_> = schedules
+            .iter()
+            .map(|(label, schedule)| (format!("{label:?}"), schedule))
+            .collect();
+        schedules.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (index, (_, schedule)) in schedules.into_iter().enumerate() {
+            schedule.write_dot(out, &format!("schedule_{index}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes [`App::to_dot`] to a file once every plugin has been built, when [`App::run`]
+/// [finishes](Plugin::finish) the plugins
+pub struct DebugGraphPlugin {
+    pub path: PathBuf,
+}
+
+impl Default for DebugGraphPlugin {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("komorebi.dot"),
+        }
+    }
+}
+
+impl Plugin for DebugGraphPlugin {
+    fn build(&self, _app: &mut App) {}
+
+    fn finish(&self, app: &mut App) {
+        match std::fs::write(&self.path, app.to_dot()) {
+            Ok(()) => info!("wrote app graph to {}", self.path.display()),
+            Err(e) => error!("failed to write app graph to {}: {e}", self.path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::DebugGraphPlugin;
+
+    struct APlugin;
+    impl Plugin for APlugin {
+        fn build(&self, app: &mut App) {
+            app.add_systems(Update, || {});
+        }
+    }
+
+    struct BPlugin;
+    impl Plugin for BPlugin {
+        fn build(&self, _app: &mut App) {}
+    }
+
+    #[test]
+    fn to_dot() {
+        let mut app = App::new();
+        app.add_plugins(APlugin).add_plugins(BPlugin);
+        let dot = app.to_dot();
+        assert!(dot.starts_with("digraph app {"));
+        assert!(dot.contains("plugin_0 [label=\"komorebi_app::debug_graph::tests::APlugin\""));
+        assert!(dot.contains("plugin_1 [label=\"komorebi_app::debug_graph::tests::BPlugin\""));
+        assert!(dot.contains("plugin_0 -> plugin_1;"));
+        assert!(dot.contains("label=\"Update\";"));
+        assert!(dot.contains("label=\"Main\";"));
+    }
+
+    #[test]
+    fn written_on_run() {
+        let path =
+            std::env::temp_dir().join(format!("komorebi_debug_graph_{}.dot", std::process::id()));
+        let mut app = App::new();
+        app.add_plugins(APlugin)
+            .add_plugins(DebugGraphPlugin { path: path.clone() });
+        assert_eq!(app.run(), AppExit::Success);
+        let dot = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(dot.contains("plugin_1 [label=\"komorebi_app::debug_graph::DebugGraphPlugin\""));
+    }
+}
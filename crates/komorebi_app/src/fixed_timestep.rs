@@ -0,0 +1,201 @@
+use std::time::{Duration, Instant};
+
+use komorebi_ecs::{ScheduleLabel, World};
+use komorebi_utils::tracing::warn;
+
+/// Runs zero or more times per update, at the fixed rate set in [`FixedTime`]
+///
+/// Gameplay and physics that must not depend on the frame rate belong here.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedUpdate;
+impl ScheduleLabel for FixedUpdate {}
+
+/// Runs [`FixedUpdate`] as many times as the accumulated time requires
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunFixedUpdateLoop;
+impl ScheduleLabel for RunFixedUpdateLoop {}
+
+/// Resource driving the [`FixedUpdate`] schedule
+///
+/// Elapsed time is accumulated every update and spent in steps of [`FixedTime::period`].
+/// What is left over after the last step is exposed as [`FixedTime::alpha`], to interpolate
+/// rendering between the last two fixed states.
+pub struct FixedTime {
+    period: Duration,
+    accumulated: Duration,
+    /// Maximum number of steps run in a single update; time beyond that is dropped so that
+    /// a slow frame cannot cause ever longer catch-up frames
+    pub max_steps_per_update: u32,
+    /// Whether [`run_fixed_update_schedule`] measures the time elapsed since its last run;
+    /// turned off by plugins that [`tick`](FixedTime::tick) it from their own clock
+    pub measure_real_time: bool,
+    last_tick: Option<Instant>,
+}
+
+impl FixedTime {
+    pub const DEFAULT_HZ: f64 = 60.0;
+    pub const DEFAULT_MAX_STEPS_PER_UPDATE: u32 = 5;
+
+    pub fn new(period: Duration) -> Self {
+        assert!(!period.is_zero(), "the fixed timestep must not be zero");
+        Self {
+            period,
+            accumulated: Duration::ZERO,
+            max_steps_per_update: Self::DEFAULT_MAX_STEPS_PER_UPDATE,
+            measure_real_time: true,
+            last_tick: None,
+        }
+    }
+
+    /// Runs [`FixedUpdate`] `hz` times per second
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is not a positive, finite number.
+    pub fn from_hz(hz: f64) -> Self {
+        assert!(
+            hz.is_finite() && hz > 0.0,
+            "the fixed update rate must be a positive, finite number of hertz, got {hz}"
+        );
+        Self::new(Duration::from_secs_f64(1.0 / hz))
+    }
+
+    /// The duration of one step
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    pub fn set_period(&mut self, period: Duration) {
+        assert!(!period.is_zero(), "the fixed timestep must not be zero");
+        self.period = period;
+    }
+
+    /// Time accumulated but not yet spent in steps
+    pub fn accumulated(&self) -> Duration {
+        self.accumulated
+    }
+
+    /// Fraction of a step left in the accumulator, in `[0, 1)` after the steps of an update
+    pub fn alpha(&self) -> f32 {
+        self.accumulated.as_secs_f32() / self.period.as_secs_f32()
+    }
+
+    /// Add elapsed time to the accumulator
+    pub fn tick(&mut self, delta: Duration) {
+        self.accumulated += delta;
+    }
+
+    /// Take one step out of the accumulator, returning `false` if not enough time accumulated
+    pub fn expend(&mut self) -> bool {
+        match self.accumulated.checked_sub(self.period) {
+            Some(remaining) => {
+                self.accumulated = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop whole steps from the accumulator, keeping the fraction of a step left
+    fn discard_steps(&mut self) -> u128 {
+        let steps = self.accumulated.as_nanos() / self.period.as_nanos();
+        let remainder = self.accumulated.as_nanos() % self.period.as_nanos();
+        self.accumulated = Duration::from_nanos(remainder as u64);
+        steps
+    }
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::from_hz(Self::DEFAULT_HZ)
+    }
+}
+
+/// The system of [`RunFixedUpdateLoop`]: accumulates the time elapsed since its last run and
+/// runs [`FixedUpdate`] once per whole step
+pub fn run_fixed_update_schedule(world: &mut World) {
+    let now = Instant::now();
+    {
+        let mut fixed_time = world.resource_mut::<FixedTime>();
+        if !fixed_time.measure_real_time {
+            fixed_time.last_tick = None;
+        } else if let Some(last_tick) = fixed_time.last_tick {
+            fixed_time.tick(now - last_tick);
+        }
+        fixed_time.last_tick = Some(now);
+    }
+
+    let mut steps = 0;
+    loop {
+        let mut fixed_time = world.resource_mut::<FixedTime>();
+        if steps == fixed_time.max_steps_per_update {
+            let dropped = fixed_time.discard_steps();
+            if dropped > 0 {
+                warn!("fixed update is falling behind, dropped {dropped} steps");
+            }
+            break;
+        }
+        if !fixed_time.expend() {
+            break;
+        }
+        drop(fixed_time);
+        let _ = world.try_run_schedule(FixedUpdate);
+        steps += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use komorebi_ecs::prelude::*;
+
+    #[test]
+    fn accumulator() {
+        let mut fixed_time = FixedTime::new(Duration::from_millis(10));
+        fixed_time.tick(Duration::from_millis(25));
+        assert!(fixed_time.expend());
+        assert!(fixed_time.expend());
+        assert!(!fixed_time.expend());
+        assert_eq!(fixed_time.accumulated(), Duration::from_millis(5));
+        assert!((fixed_time.alpha() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "positive, finite number of hertz, got 0")]
+    fn zero_hz() {
+        FixedTime::from_hz(0.0);
+    }
+
+    #[derive(Default)]
+    struct Steps(u32);
+
+    fn app_with_steps() -> crate::App {
+        let mut app = crate::App::new();
+        app.init_resource::<Steps>()
+            .add_systems(FixedUpdate, |mut steps: ResMut<Steps>| steps.0 += 1);
+        app
+    }
+
+    #[test]
+    fn runs_fixed_update_per_step() {
+        let mut app = app_with_steps();
+        app.world
+            .resource_mut::<FixedTime>()
+            .tick(Duration::from_secs_f64(3.5 / 60.0));
+        app.world.run_schedule(RunFixedUpdateLoop);
+        assert_eq!(app.world.resource::<Steps>().0, 3);
+        assert!(app.world.resource::<FixedTime>().alpha() < 1.0);
+    }
+
+    #[test]
+    fn limits_catch_up_steps() {
+        let mut app = app_with_steps();
+        app.world.resource_mut::<FixedTime>().max_steps_per_update = 2;
+        app.world
+            .resource_mut::<FixedTime>()
+            .tick(Duration::from_secs_f64(10.5 / 60.0));
+        app.world.run_schedule(RunFixedUpdateLoop);
+        assert_eq!(app.world.resource::<Steps>().0, 2);
+        assert!(app.world.resource::<FixedTime>().alpha() < 1.0);
+    }
+}
@@ -1,13 +1,33 @@
 mod app;
+mod debug_graph;
+mod fixed_timestep;
+mod log;
+mod main_schedule;
+mod panic_handler;
 mod plugin;
 mod plugin_group;
+mod schedule_runner;
+mod state;
+mod sub_app;
 
 pub use app::*;
+pub use debug_graph::*;
+pub use fixed_timestep::*;
+pub use main_schedule::*;
+pub use panic_handler::*;
 pub use plugin::*;
 pub use plugin_group::*;
+pub use schedule_runner::*;
+pub use state::*;
+pub use sub_app::*;
 
 #[allow(missing_docs)]
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{app::App, Plugin, PluginGroup};
+    pub use crate::{
+        app::App, in_state, AppExit, DebugGraphPlugin, First, FixedTime, FixedUpdate, Last, Main,
+        NextState, OnEnter, OnExit, OnTransition, PanicHandlerPlugin, Plugin, PluginDependency,
+        PluginGroup, PostStartup, PostUpdate, PreStartup, PreUpdate, Startup, State, StateScoped,
+        States, Update,
+    };
 }
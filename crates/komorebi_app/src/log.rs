@@ -0,0 +1,58 @@
+use komorebi_utils::{tracing::warn, LogError, LogPlugin};
+
+use crate::{App, BoxedError, Plugin};
+
+impl Plugin for LogPlugin {
+    fn build(&self, app: &mut App) {
+        self.try_build(app).unwrap();
+    }
+
+    /// Fails on invalid settings, but only warns when a subscriber is already installed, like
+    /// by an earlier app of the process
+    ///
+    /// Inserts the [`LogGuard`](komorebi_utils::LogGuard) resource, flushing the outputs when
+    /// the app is dropped.
+    fn try_build(&self, app: &mut App) -> Result<(), BoxedError> {
+        match self.install() {
+            Ok(guard) => {
+                app.insert_resource(guard);
+            }
+            Err(LogError::AlreadyInstalled) => {
+                warn!("{}, not installing another", LogError::AlreadyInstalled);
+            }
+            Err(e) => Err(e)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use komorebi_utils::{LogFile, LogPlugin};
+
+    #[test]
+    fn install_once() {
+        let directory =
+            std::env::temp_dir().join(format!("komorebi_install_once_{}", std::process::id()));
+        // With the `trace_chrome` feature, keep the trace out of the source tree
+        std::env::set_var("TRACE_CHROME", directory.with_extension("json"));
+        App::new().add_plugins(LogPlugin::default());
+        // Outputs are not created for a subscriber that cannot be installed
+        App::new().add_plugins(LogPlugin {
+            file: Some(LogFile::new(&directory, "game")),
+            ..Default::default()
+        });
+        assert!(!directory.exists());
+
+        let error = App::new()
+            .try_add_plugins(LogPlugin {
+                filter: "komorebi=loud".to_string(),
+                ..Default::default()
+            })
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("invalid log filter"));
+        let _ = std::fs::remove_file(directory.with_extension("json"));
+    }
+}
@@ -0,0 +1,149 @@
+use std::{borrow::Cow, sync::Arc};
+
+use komorebi_ecs::{Access, ScheduleLabel, System, World};
+
+use crate::{RunFixedUpdateLoop, StateTransition};
+
+/// The schedule run by [`App::update`](crate::App), which in turn runs the schedules listed
+/// in [`MainScheduleOrder`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Main;
+impl ScheduleLabel for Main {}
+
+/// Runs once, before the first [`First`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreStartup;
+impl ScheduleLabel for PreStartup {}
+
+/// Runs once, before the first [`First`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Startup;
+impl ScheduleLabel for Startup {}
+
+/// Runs once, before the first [`First`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PostStartup;
+impl ScheduleLabel for PostStartup {}
+
+/// Runs at the start of every update
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct First;
+impl ScheduleLabel for First {}
+
+/// Runs before [`Update`], e.g. to process input
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreUpdate;
+impl ScheduleLabel for PreUpdate {}
+
+/// Runs every update; most gameplay systems belong here
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Update;
+impl ScheduleLabel for Update {}
+
+/// Runs after [`Update`], e.g. to react to gameplay changes
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PostUpdate;
+impl ScheduleLabel for PostUpdate {}
+
+/// Runs at the end of every update
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Last;
+impl ScheduleLabel for Last {}
+
+/// Resource listing the schedules run by [`Main`], in order
+pub struct MainScheduleOrder {
+    /// Schedules run once, on the first update
+    pub startup_labels: Vec<Arc<dyn ScheduleLabel>>,
+    /// Schedules run on every update
+    pub labels: Vec<Arc<dyn ScheduleLabel>>,
+}
+
+impl Default for MainScheduleOrder {
+    fn default() -> Self {
+        Self {
+            startup_labels: vec![
+                Arc::new(PreStartup),
+                Arc::new(Startup),
+                Arc::new(PostStartup),
+            ],
+            labels: vec![
+                Arc::new(First),
+                Arc::new(PreUpdate),
+                Arc::new(StateTransition),
+                Arc::new(RunFixedUpdateLoop),
+                Arc::new(Update),
+                Arc::new(PostUpdate),
+                Arc::new(Last),
+            ],
+        }
+    }
+}
+
+impl MainScheduleOrder {
+    /// Run `schedule` right after `after` on every update
+    ///
+    /// # Panics
+    ///
+    /// Panics if `after` is not in the order.
+    pub fn insert_after(&mut self, after: impl ScheduleLabel, schedule: impl ScheduleLabel) {
+        let after: &dyn ScheduleLabel = &after;
+        let index = self
+            .labels
+            .iter()
+            .position(|label| **label == *after)
+            .unwrap_or_else(|| panic!("schedule {after:?} is not in the main schedule order"));
+        self.labels.insert(index + 1, Arc::new(schedule));
+    }
+}
+
+/// The exclusive system of the [`Main`] schedule
+pub(crate) struct RunMain {
+    startup_done: bool,
+    access: Access,
+}
+
+impl RunMain {
+    pub(crate) fn new() -> Self {
+        Self {
+            startup_done: false,
+            access: Access::world(),
+        }
+    }
+}
+
+impl System for RunMain {
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        "komorebi_app::Main::run_main".into()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        true
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn initialize(&mut self, _world: &mut World) {}
+
+    fn run_shared(&mut self, _input: (), _world: &World) {
+        panic!("the main schedule system is exclusive");
+    }
+
+    fn run(&mut self, _input: (), world: &mut World) {
+        world.resource_scope(|world, order: &mut MainScheduleOrder| {
+            if !self.startup_done {
+                for label in &order.startup_labels {
+                    let _ = world.try_run_schedule_ref(&**label);
+                }
+                self.startup_done = true;
+            }
+            for label in &order.labels {
+                let _ = world.try_run_schedule_ref(&**label);
+            }
+        });
+    }
+}
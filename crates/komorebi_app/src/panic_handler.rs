@@ -0,0 +1,246 @@
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::RefCell,
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{self, Write as _},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use komorebi_ecs::running_system;
+use komorebi_utils::{keep_recent_log_lines, recent_log_lines};
+
+use crate::{running_plugin, App, Plugin};
+
+/// Writes a crash report when a panic is not caught before reaching the app
+///
+/// Reports are named `crash-{timestamp}-{process id}-{count}.txt`, and hold the engine version,
+/// the panic message and location, the thread, plugin and system running when it happened, a
+/// backtrace, and the last lines logged through [`LogPlugin`](komorebi_utils::LogPlugin).
+///
+/// A panic in the runner unwinds to [`App::run`], which returns
+/// [`AppExit::PANIC`](crate::AppExit::PANIC) once the app is dropped, flushing the logs and
+/// traces. A panic while building a plugin stops adding plugins, and [`App::run`] then returns
+/// [`AppExit::PANIC`](crate::AppExit::PANIC) without calling the runner. Panics caught elsewhere,
+/// like those of tasks, get no report.
+///
+/// The panic hook keeping the details of panics is installed for the whole process.
+#[derive(Clone, Debug)]
+pub struct PanicHandlerPlugin {
+    pub directory: PathBuf,
+    /// Log lines included in reports
+    pub log_lines: usize,
+    /// Written in reports, the version of `komorebi` when added by its `DefaultPlugins`
+    pub engine_version: &'static str,
+}
+
+impl Default for PanicHandlerPlugin {
+    fn default() -> Self {
+        Self {
+            directory: "crash_reports".into(),
+            log_lines: 50,
+            engine_version: "unknown",
+        }
+    }
+}
+
+/// The settings of the [`PanicHandlerPlugin`] of an app
+struct CrashReports(PanicHandlerPlugin);
+
+impl Plugin for PanicHandlerPlugin {
+    fn build(&self, app: &mut App) {
+        keep_recent_log_lines(self.log_lines);
+        app.insert_resource(CrashReports(self.clone()));
+
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let payload = info.payload();
+                let details = PanicDetails {
+                    message: match info.location() {
+                        Some(location) => {
+                            format!("panicked at {location}: {}", payload_message(payload))
+                        }
+                        None => format!("panicked: {}", payload_message(payload)),
+                    },
+                    thread: Some(
+                        std::thread::current()
+                            .name()
+                            .unwrap_or("unnamed")
+                            .to_string(),
+                    ),
+                    plugin: running_plugin(),
+                    system: running_system().map(|name| name.into_owned()),
+                    backtrace: Some(Backtrace::force_capture()),
+                    log_lines: recent_log_lines(),
+                };
+                LAST_PANIC.set(Some((payload_message(payload).to_string(), details)));
+                previous(info);
+            }));
+        });
+    }
+}
+
+thread_local! {
+    /// The payload message and details of the last panic of the thread, kept by the hook
+    static LAST_PANIC: RefCell<Option<(String, PanicDetails)>> = const { RefCell::new(None) };
+}
+
+/// What a crash report says about a panic
+struct PanicDetails {
+    message: String,
+    thread: Option<String>,
+    plugin: Option<String>,
+    system: Option<String>,
+    backtrace: Option<Backtrace>,
+    log_lines: Vec<String>,
+}
+
+impl PanicDetails {
+    /// The details the hook kept for `payload`, or what the payload tells for panics that
+    /// happened on another thread, like in a task
+    fn of(payload: &(dyn Any + Send)) -> Self {
+        let message = payload_message(payload);
+        match LAST_PANIC.take() {
+            Some((last_message, details)) if last_message == message => details,
+            _ => Self {
+                message: format!("panicked: {message}"),
+                thread: None,
+                plugin: None,
+                system: None,
+                backtrace: None,
+                log_lines: recent_log_lines(),
+            },
+        }
+    }
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
+/// The settings of the [`PanicHandlerPlugin`] of `app`, if it has one
+pub(crate) fn crash_reports(app: &App) -> Option<PanicHandlerPlugin> {
+    app.world
+        .get_resource::<CrashReports>()
+        .map(|crash_reports| crash_reports.0.clone())
+}
+
+impl PanicHandlerPlugin {
+    /// Writes the report of a panic caught by the app
+    pub(crate) fn report(&self, payload: &(dyn Any + Send)) {
+        match write_crash_report(self, &PanicDetails::of(payload)) {
+            Ok(path) => eprintln!("crash report written to {}", path.display()),
+            Err(e) => eprintln!("cannot write crash report: {e}"),
+        }
+    }
+}
+
+/// Writes the report of a panic in the directory of `settings`, returning its path
+fn write_crash_report(settings: &PanicHandlerPlugin, panic: &PanicDetails) -> io::Result<PathBuf> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+
+    let mut report = String::from("Komorebi crash report\n\n");
+    let none = "none";
+    let _ = writeln!(report, "Engine version: {}", settings.engine_version);
+    let _ = writeln!(
+        report,
+        "Thread: {}",
+        panic.thread.as_deref().unwrap_or(none)
+    );
+    let _ = writeln!(
+        report,
+        "Plugin: {}",
+        panic.plugin.as_deref().unwrap_or(none)
+    );
+    let _ = writeln!(
+        report,
+        "System: {}",
+        panic.system.as_deref().unwrap_or(none)
+    );
+    let _ = writeln!(report, "Panic: {}", panic.message);
+    let _ = match &panic.backtrace {
+        Some(backtrace) => writeln!(report, "\nBacktrace:\n{backtrace}"),
+        None => writeln!(
+            report,
+            "\nBacktrace: none, the panic happened on another thread\n"
+        ),
+    };
+    let _ = writeln!(report, "Last log lines:");
+    for line in &panic.log_lines {
+        let _ = writeln!(report, "{line}");
+    }
+
+    std::fs::create_dir_all(&settings.directory)?;
+    let path = settings.directory.join(format!(
+        "crash-{timestamp}-{}-{count}.txt",
+        std::process::id()
+    ));
+    // Never replace an earlier report
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    file.write_all(report.as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::PanicHandlerPlugin;
+
+    struct CrashingPlugin;
+    impl Plugin for CrashingPlugin {
+        fn build(&self, _app: &mut App) {
+            panic!("crashing plugin");
+        }
+    }
+
+    #[test]
+    fn crash_report() {
+        let directory =
+            std::env::temp_dir().join(format!("komorebi_crash_reports_{}", std::process::id()));
+        let mut app = App::new();
+        app.add_plugins(PanicHandlerPlugin {
+            directory: directory.clone(),
+            engine_version: "1.2.3",
+            ..Default::default()
+        })
+        .add_plugins(CrashingPlugin)
+        // Not added once a plugin panicked
+        .add_plugins(DebugGraphPlugin::default());
+        assert!(!app.is_plugin_added::<DebugGraphPlugin>());
+        assert_eq!(app.run(), AppExit::PANIC);
+
+        let reports: Vec<_> = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        let report = std::fs::read_to_string(&reports[0]).unwrap();
+        let _ = std::fs::remove_dir_all(&directory);
+        assert_eq!(reports.len(), 1);
+        assert!(report.contains("Engine version: 1.2.3"));
+        assert!(report.contains("Thread: panic_handler::tests::crash_report"));
+        assert!(report.contains("Plugin: komorebi_app::panic_handler::tests::CrashingPlugin"));
+        assert!(report.contains("System: none"));
+        assert!(report.contains("src/panic_handler.rs"));
+        assert!(report.contains(": crashing plugin"));
+    }
+}
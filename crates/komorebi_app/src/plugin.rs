@@ -1,10 +1,21 @@
-use crate::App;
+use std::{any::TypeId, cell::RefCell};
+
+use crate::{App, BoxedError};
 use downcast_rs::{impl_downcast, Downcast};
 
 pub trait Plugin: Downcast {
     /// Configure the `App`
     fn build(&self, app: &mut App);
 
+    /// Configure the `App`, reporting invalid configuration as an error
+    ///
+    /// This is what the app calls, and it defaults to [`Plugin::build`]. Plugins that can
+    /// fail override it, and usually have `build` unwrap it.
+    fn try_build(&self, app: &mut App) -> Result<(), BoxedError> {
+        self.build(app);
+        Ok(())
+    }
+
     fn ready(&self, _app: &App) -> bool {
         true
     }
@@ -21,10 +32,64 @@ pub trait Plugin: Downcast {
     fn is_unique(&self) -> bool {
         true
     }
+
+    /// Plugins that must be added before this one
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        Vec::new()
+    }
 }
 
 impl_downcast!(Plugin);
 
+thread_local! {
+    static RUNNING_PLUGIN: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Name of the plugin being built, finished or cleaned up on the current thread, for panic
+/// reports
+pub fn running_plugin() -> Option<String> {
+    RUNNING_PLUGIN.with_borrow(|name| name.clone())
+}
+
+/// Sets [`running_plugin`] until dropped, also when a panicking plugin unwinds
+pub(crate) struct RunningPlugin(Option<String>);
+
+impl RunningPlugin {
+    pub(crate) fn enter(name: &str) -> Self {
+        Self(RUNNING_PLUGIN.replace(Some(name.to_string())))
+    }
+}
+
+impl Drop for RunningPlugin {
+    fn drop(&mut self) {
+        RUNNING_PLUGIN.set(self.0.take());
+    }
+}
+
+/// A plugin type required by another plugin, see [`Plugin::dependencies`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PluginDependency {
+    type_id: TypeId,
+    name: &'static str,
+}
+
+impl PluginDependency {
+    pub fn of<T: Plugin>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+        }
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
 /// Types that represent a set of [`Plugin`]s
 pub trait Plugins<Marker>: sealed::Plugins<Marker> {}
 
@@ -34,25 +99,28 @@ mod sealed {
     use crate::{App, AppError, Plugin, PluginGroup};
 
     pub trait Plugins<Marker> {
-        fn add_to_app(self, app: &mut App);
+        fn try_add_to_app(self, app: &mut App) -> Result<(), AppError>;
     }
 
     pub struct PluginMarker;
     pub struct PluginGroupMarker;
+    pub struct BoxedPluginMarker;
 
     impl<P: Plugin> Plugins<PluginMarker> for P {
-        fn add_to_app(self, app: &mut App) {
-            if let Err(AppError::DuplicatePlugin { plugin_name }) =
-                app.add_boxed_plugin(Box::new(self))
-            {
-                panic!("Duplicate plugin: {plugin_name}");
-            }
+        fn try_add_to_app(self, app: &mut App) -> Result<(), AppError> {
+            app.add_boxed_plugin(Box::new(self)).map(|_| ())
+        }
+    }
+
+    impl Plugins<BoxedPluginMarker> for Box<dyn Plugin> {
+        fn try_add_to_app(self, app: &mut App) -> Result<(), AppError> {
+            app.add_boxed_plugin(self).map(|_| ())
         }
     }
 
     impl<P: PluginGroup> Plugins<PluginGroupMarker> for P {
-        fn add_to_app(self, app: &mut App) {
-            self.build().finish(app);
+        fn try_add_to_app(self, app: &mut App) -> Result<(), AppError> {
+            self.build().try_finish(app)
         }
     }
 }
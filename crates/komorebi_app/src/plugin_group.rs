@@ -27,6 +27,7 @@ pub struct PluginGroupBuilder {
     group_name: String,
     plugins: HashMap<TypeId, PluginEntry>,
     order: Vec<TypeId>,
+    sort_by_dependencies: bool,
 }
 
 impl PluginGroupBuilder {
@@ -35,9 +36,17 @@ impl PluginGroupBuilder {
             group_name: PG::name(),
             plugins: Default::default(),
             order: Default::default(),
+            sort_by_dependencies: false,
         }
     }
 
+    /// Build each plugin after the plugins of this group it [depends on](Plugin::dependencies),
+    /// rather than strictly in the order they were added
+    pub fn sort_by_dependencies(mut self) -> Self {
+        self.sort_by_dependencies = true;
+        self
+    }
+
     /// Adds the plugin [`Plugin`] at the end of this group builder.
     /// If the plugin already exists, it is removed from its previous place.
     #[allow(clippy::should_implement_trait)]
@@ -48,16 +57,98 @@ impl PluginGroupBuilder {
         self
     }
 
+    /// Adds the plugin [`Plugin`] right before the plugin `Target`.
+    /// If the plugin already exists, it is removed from its previous place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Target` is not in this group.
+    pub fn add_before<Target: Plugin, T: Plugin>(mut self, plugin: T) -> Self {
+        let target_index = self.index_of::<Target>();
+        self.order.insert(target_index, TypeId::of::<T>());
+        self.upsert_plugin_state(plugin, target_index);
+        self
+    }
+
+    /// Adds the plugin [`Plugin`] right after the plugin `Target`.
+    /// If the plugin already exists, it is removed from its previous place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Target` is not in this group.
+    pub fn add_after<Target: Plugin, T: Plugin>(mut self, plugin: T) -> Self {
+        let target_index = self.index_of::<Target>() + 1;
+        self.order.insert(target_index, TypeId::of::<T>());
+        self.upsert_plugin_state(plugin, target_index);
+        self
+    }
+
+    /// Adds the plugins of another group at the end of this one, in their order.
+    /// Plugins already in this group are moved, and disabled plugins stay disabled.
+    pub fn add_group(mut self, group: impl PluginGroup) -> Self {
+        let PluginGroupBuilder {
+            mut plugins,
+            order,
+            sort_by_dependencies,
+            ..
+        } = group.build();
+        self.sort_by_dependencies |= sort_by_dependencies;
+        for ty in order {
+            if let Some(entry) = plugins.remove(&ty) {
+                let target_index = self.order.len();
+                self.order.push(ty);
+                self.upsert_plugin_entry(ty, entry, target_index);
+            }
+        }
+        self
+    }
+
     pub fn set<T: Plugin>(mut self, plugin: T) -> Self {
-        let entry = self.plugins.get_mut(&TypeId::of::<T>()).unwrap_or_else(|| {
+        self.entry_mut::<T>().plugin = Box::new(plugin);
+        self
+    }
+
+    /// Enables a plugin previously disabled with [`disable`](Self::disable)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin is not in this group.
+    pub fn enable<T: Plugin>(mut self) -> Self {
+        self.entry_mut::<T>().enabled = true;
+        self
+    }
+
+    /// Keeps a plugin in this group, but skips it when the group is added to an app
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin is not in this group.
+    pub fn disable<T: Plugin>(mut self) -> Self {
+        self.entry_mut::<T>().enabled = false;
+        self
+    }
+
+    fn entry_mut<T: Plugin>(&mut self) -> &mut PluginEntry {
+        self.plugins.get_mut(&TypeId::of::<T>()).unwrap_or_else(|| {
             panic!(
                 "Plugin {} does not exist in group {}",
                 std::any::type_name::<T>(),
                 self.group_name
             )
-        });
-        entry.plugin = Box::new(plugin);
-        self
+        })
+    }
+
+    fn index_of<T: Plugin>(&self) -> usize {
+        self.order
+            .iter()
+            .position(|ty| *ty == TypeId::of::<T>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Plugin {} does not exist in group {}",
+                    std::any::type_name::<T>(),
+                    self.group_name
+                )
+            })
     }
 
     /// Consumes the [`PluginGroupBuilder`] and [builds](Plugin::build) the contained [`Plugin`]s
@@ -65,35 +156,88 @@ impl PluginGroupBuilder {
     ///
     /// # Panics
     ///
-    /// Panics if one of the plugin in the group was already added to the application.
-    pub fn finish(mut self, app: &mut App) {
+    /// Panics if adding a plugin fails, see [`PluginGroupBuilder::try_finish`].
+    pub fn finish(self, app: &mut App) {
+        if let Err(e) = self.try_finish(app) {
+            panic!("{e}");
+        }
+    }
+
+    /// Consumes the [`PluginGroupBuilder`] and [builds](Plugin::build) the contained [`Plugin`]s
+    /// in the order specified, stopping at the first failure.
+    ///
+    /// Fails if one of the plugins in the group was already added to the application, is
+    /// missing a dependency or fails to build, or if sorted plugins depend on each other in a
+    /// cycle. The error names the group.
+    pub fn try_finish(mut self, app: &mut App) -> Result<(), AppError> {
+        let in_group = |source| AppError::InGroup {
+            group_name: self.group_name.clone(),
+            source: Box::new(source),
+        };
+        if self.sort_by_dependencies {
+            self.order = self.dependency_order().map_err(in_group)?;
+        }
         for ty in &self.order {
             if let Some(entry) = self.plugins.remove(ty) {
                 if entry.enabled {
                     debug!("added plugin: {}", entry.plugin.name());
-                    if let Err(AppError::DuplicatePlugin { plugin_name }) =
-                        app.add_boxed_plugin(entry.plugin)
-                    {
-                        panic!(
-                            "Error adding plugin {} in group {}: plugin was already added",
-                            plugin_name, self.group_name
-                        );
-                    }
+                    app.add_boxed_plugin(entry.plugin).map_err(in_group)?;
                 }
             }
         }
+        Ok(())
+    }
+
+    /// `order`, moving each plugin after those of the group it depends on, and otherwise
+    /// keeping the order plugins were added in
+    fn dependency_order(&self) -> Result<Vec<TypeId>, AppError> {
+        let mut remaining: Vec<(TypeId, Vec<TypeId>)> = self
+            .order
+            .iter()
+            .map(|ty| {
+                let dependencies = self.plugins[ty]
+                    .plugin
+                    .dependencies()
+                    .iter()
+                    .map(|dependency| dependency.type_id())
+                    .filter(|dependency| self.order.contains(dependency))
+                    .collect();
+                (*ty, dependencies)
+            })
+            .collect();
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let Some(next) = remaining
+                .iter()
+                .position(|(_, dependencies)| dependencies.iter().all(|d| order.contains(d)))
+            else {
+                return Err(AppError::DependencyCycle {
+                    plugin_names: remaining
+                        .iter()
+                        .map(|(ty, _)| self.plugins[ty].plugin.name().to_string())
+                        .collect(),
+                });
+            };
+            order.push(remaining.remove(next).0);
+        }
+        Ok(order)
     }
 
     /// Insert the new plugin as enabled, and removes its previous ordering if it was
     /// already present
     fn upsert_plugin_state<T: Plugin>(&mut self, plugin: T, added_at_index: usize) {
-        if let Some(old_entry) = self.plugins.insert(
+        self.upsert_plugin_entry(
             TypeId::of::<T>(),
             PluginEntry {
                 plugin: Box::new(plugin),
                 enabled: true,
             },
-        ) {
+            added_at_index,
+        );
+    }
+
+    fn upsert_plugin_entry(&mut self, ty: TypeId, entry: PluginEntry, added_at_index: usize) {
+        if let Some(old_entry) = self.plugins.insert(ty, entry) {
             if old_entry.enabled {
                 warn!(
                     "You are replacing plugin '{}' that was not disabled",
@@ -105,7 +249,7 @@ impl PluginGroupBuilder {
                 .order
                 .iter()
                 .enumerate()
-                .find(|(i, ty)| *i != added_at_index && **ty == TypeId::of::<T>())
+                .find(|(i, other)| *i != added_at_index && **other == ty)
                 .map(|(i, _)| i)
             {
                 self.order.remove(to_remove);
@@ -119,3 +263,179 @@ impl PluginGroup for PluginGroupBuilder {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::{AppError, BoxedError, PluginDependency, PluginGroupBuilder};
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    struct WindowPlugin;
+    impl Plugin for WindowPlugin {
+        fn build(&self, app: &mut App) {
+            app.world.resource_mut::<Log>().0.push("window");
+        }
+    }
+
+    struct RenderPlugin;
+    impl Plugin for RenderPlugin {
+        fn build(&self, app: &mut App) {
+            app.world.resource_mut::<Log>().0.push("render");
+        }
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![PluginDependency::of::<WindowPlugin>()]
+        }
+    }
+
+    struct TestPlugins;
+    impl PluginGroup for TestPlugins {
+        fn build(self) -> PluginGroupBuilder {
+            PluginGroupBuilder::start::<Self>()
+                .add(RenderPlugin)
+                .add(WindowPlugin)
+        }
+    }
+
+    #[test]
+    fn sort_by_dependencies() {
+        let mut app = App::new();
+        app.init_resource::<Log>()
+            .add_plugins(TestPlugins.build().sort_by_dependencies());
+        assert_eq!(app.world.resource::<Log>().0, ["window", "render"]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "in group komorebi_app::plugin_group::tests::TestPlugins: plugin \
+                    komorebi_app::plugin_group::tests::RenderPlugin requires plugin \
+                    komorebi_app::plugin_group::tests::WindowPlugin"
+    )]
+    fn unsorted_dependencies() {
+        let mut app = App::new();
+        app.init_resource::<Log>().add_plugins(TestPlugins);
+    }
+
+    #[test]
+    fn editing() {
+        struct AudioPlugin;
+        impl Plugin for AudioPlugin {
+            fn build(&self, app: &mut App) {
+                app.world.resource_mut::<Log>().0.push("audio");
+            }
+        }
+
+        struct InputPlugin;
+        impl Plugin for InputPlugin {
+            fn build(&self, app: &mut App) {
+                app.world.resource_mut::<Log>().0.push("input");
+            }
+        }
+
+        struct ExtraPlugins;
+        impl PluginGroup for ExtraPlugins {
+            fn build(self) -> PluginGroupBuilder {
+                PluginGroupBuilder::start::<Self>()
+                    .add(InputPlugin)
+                    .add(WindowPlugin)
+                    .disable::<InputPlugin>()
+            }
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Log>().add_plugins(
+            PluginGroupBuilder::start::<TestPlugins>()
+                .add(RenderPlugin)
+                .add_before::<RenderPlugin, _>(AudioPlugin)
+                .add_group(ExtraPlugins)
+                .add_after::<WindowPlugin, _>(RenderPlugin)
+                .disable::<AudioPlugin>()
+                .enable::<AudioPlugin>(),
+        );
+        // InputPlugin stays disabled, and RenderPlugin moved after WindowPlugin
+        assert_eq!(app.world.resource::<Log>().0, ["audio", "window", "render"]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Plugin komorebi_app::plugin_group::tests::WindowPlugin does not exist in \
+                    group komorebi_app::plugin_group::tests::TestPlugins"
+    )]
+    fn missing_target() {
+        PluginGroupBuilder::start::<TestPlugins>().add_after::<WindowPlugin, _>(RenderPlugin);
+    }
+
+    #[test]
+    fn try_build_error_names_group() {
+        struct Config(u32);
+
+        struct ConfigPlugin;
+        impl Plugin for ConfigPlugin {
+            fn build(&self, app: &mut App) {
+                self.try_build(app).unwrap();
+            }
+            fn try_build(&self, app: &mut App) -> Result<(), BoxedError> {
+                match app.world.get_resource::<Config>() {
+                    Some(config) if config.0 > 0 => Ok(()),
+                    _ => Err("Config must be positive".into()),
+                }
+            }
+        }
+
+        struct ConfigPlugins;
+        impl PluginGroup for ConfigPlugins {
+            fn build(self) -> PluginGroupBuilder {
+                PluginGroupBuilder::start::<Self>()
+                    .add(WindowPlugin)
+                    .add(ConfigPlugin)
+            }
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Log>().insert_resource(Config(0));
+        let error = app.try_add_plugins(ConfigPlugins).err().unwrap();
+        assert!(matches!(
+            &error,
+            AppError::InGroup { source, .. } if matches!(**source, AppError::PluginBuild { .. })
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Error adding plugin in group komorebi_app::plugin_group::tests::\
+             try_build_error_names_group::ConfigPlugins: plugin komorebi_app::plugin_group::\
+             tests::try_build_error_names_group::ConfigPlugin failed to build: Config must be \
+             positive"
+        );
+        assert!(app.is_plugin_added::<WindowPlugin>());
+        assert!(!app.is_plugin_added::<ConfigPlugin>());
+
+        app.insert_resource(Config(1));
+        app.try_add_plugins(ConfigPlugin).unwrap();
+        assert!(app.is_plugin_added::<ConfigPlugin>());
+    }
+
+    #[test]
+    #[should_panic(expected = "depend on each other in a cycle")]
+    fn dependency_cycle() {
+        struct A;
+        impl Plugin for A {
+            fn build(&self, _app: &mut App) {}
+            fn dependencies(&self) -> Vec<PluginDependency> {
+                vec![PluginDependency::of::<B>()]
+            }
+        }
+        struct B;
+        impl Plugin for B {
+            fn build(&self, _app: &mut App) {}
+            fn dependencies(&self) -> Vec<PluginDependency> {
+                vec![PluginDependency::of::<A>()]
+            }
+        }
+        App::new().add_plugins(
+            PluginGroupBuilder::start::<TestPlugins>()
+                .add(A)
+                .add(B)
+                .sort_by_dependencies(),
+        );
+    }
+}
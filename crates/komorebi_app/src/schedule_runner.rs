@@ -0,0 +1,131 @@
+use std::time::{Duration, Instant};
+
+use crate::{App, Plugin};
+
+/// How [`ScheduleRunnerPlugin`] updates the app
+#[derive(Clone, Copy, Debug)]
+pub enum RunMode {
+    /// Update until an [`AppExit`](crate::AppExit) event is sent, waiting between updates so that each
+    /// takes at least `wait`
+    Loop { wait: Option<Duration> },
+    /// Update once
+    Once,
+}
+
+impl Default for RunMode {
+    fn default() -> Self {
+        RunMode::Loop { wait: None }
+    }
+}
+
+/// Runs the app without a window, for servers and tests
+///
+/// Replaces the runner installed by windowing plugins when added after them, for example
+/// with [`PluginGroupBuilder::add`](crate::PluginGroupBuilder::add).
+#[derive(Default)]
+pub struct ScheduleRunnerPlugin {
+    pub run_mode: RunMode,
+}
+
+impl ScheduleRunnerPlugin {
+    /// Update the app once
+    pub fn run_once() -> Self {
+        Self {
+            run_mode: RunMode::Once,
+        }
+    }
+
+    /// Update the app at most once every `wait`, until it exits
+    pub fn run_loop(wait: Duration) -> Self {
+        Self {
+            run_mode: RunMode::Loop { wait: Some(wait) },
+        }
+    }
+}
+
+impl Plugin for ScheduleRunnerPlugin {
+    fn build(&self, app: &mut App) {
+        let run_mode = self.run_mode;
+        app.set_runner(move |mut app| match run_mode {
+            RunMode::Once => {
+                app.update();
+                app.should_exit().unwrap_or_default()
+            }
+            RunMode::Loop { wait } => loop {
+                let start = Instant::now();
+                app.update();
+                if let Some(exit) = app.should_exit() {
+                    return exit;
+                }
+                if let Some(wait) = wait {
+                    std::thread::sleep(wait.saturating_sub(start.elapsed()));
+                }
+            },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use crate::prelude::*;
+    use crate::{AppExit, PluginGroupBuilder, ScheduleRunnerPlugin};
+    use komorebi_ecs::prelude::*;
+
+    /// Shared with the test, since the runner consumes the app
+    #[derive(Default, Clone)]
+    struct Frames(Arc<AtomicU32>);
+
+    fn count_and_exit(frames: Res<Frames>, mut exit: EventWriter<AppExit>) {
+        if frames.0.fetch_add(1, Ordering::Relaxed) + 1 == 3 {
+            exit.send(AppExit::error());
+        }
+    }
+
+    #[test]
+    fn loop_until_exit() {
+        let frames = Frames::default();
+        let start = Instant::now();
+        let exit = App::new()
+            .insert_resource(frames.clone())
+            .add_systems(Update, count_and_exit)
+            .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_millis(10)))
+            .run();
+        assert_eq!(exit, AppExit::error());
+        assert_eq!(frames.0.load(Ordering::Relaxed), 3);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn replaces_windowed_runner() {
+        struct WindowedPlugin;
+        impl Plugin for WindowedPlugin {
+            fn build(&self, app: &mut App) {
+                app.set_runner(|_| panic!("no window in tests"));
+            }
+        }
+
+        struct WindowedPlugins;
+        impl PluginGroup for WindowedPlugins {
+            fn build(self) -> PluginGroupBuilder {
+                PluginGroupBuilder::start::<Self>().add(WindowedPlugin)
+            }
+        }
+
+        let frames = Frames::default();
+        let exit = App::new()
+            .insert_resource(frames.clone())
+            .add_systems(Update, count_and_exit)
+            .add_plugins(
+                WindowedPlugins
+                    .build()
+                    .add(ScheduleRunnerPlugin::run_once()),
+            )
+            .run();
+        assert_eq!(exit, AppExit::Success);
+        assert_eq!(frames.0.load(Ordering::Relaxed), 1);
+    }
+}
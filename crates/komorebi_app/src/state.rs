@@ -0,0 +1,229 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::mem;
+
+use komorebi_ecs::{
+    common_conditions::run_once, Entity, IntoSystemConfigs, Res, ScheduleLabel, VecStorage, World,
+};
+
+use crate::App;
+
+/// A finite set of application states, such as menu, loading, playing and paused
+///
+/// Register it with [`App::add_state`], then read it with [`State`] and request changes
+/// with [`NextState`].
+pub trait States: Clone + Eq + Hash + Debug + Default + Send + Sync + 'static {}
+
+/// Resource holding the current value of `S`
+///
+/// It only changes during [`StateTransition`], after a value was set in [`NextState`].
+#[derive(Debug, Default)]
+pub struct State<S: States>(S);
+
+impl<S: States> State<S> {
+    pub fn new(state: S) -> Self {
+        Self(state)
+    }
+
+    pub fn get(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S: States> PartialEq<S> for State<S> {
+    fn eq(&self, other: &S) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Resource requesting a transition of `S`, applied during the next [`StateTransition`]
+#[derive(Debug, Default)]
+pub struct NextState<S: States>(pub Option<S>);
+
+impl<S: States> NextState<S> {
+    pub fn set(&mut self, state: S) {
+        self.0 = Some(state);
+    }
+}
+
+/// Runs when the state `S` is entered
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnEnter<S: States>(pub S);
+impl<S: States> ScheduleLabel for OnEnter<S> {}
+
+/// Runs when the state `S` is exited
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnExit<S: States>(pub S);
+impl<S: States> ScheduleLabel for OnExit<S> {}
+
+/// Runs when the state `S` goes from `from` to `to`, between [`OnExit`] and [`OnEnter`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnTransition<S: States> {
+    pub from: S,
+    pub to: S,
+}
+impl<S: States> ScheduleLabel for OnTransition<S> {}
+
+/// Applies the pending [`NextState`]s, right after [`PreUpdate`](crate::PreUpdate)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StateTransition;
+impl ScheduleLabel for StateTransition {}
+
+/// Component despawning its entity when the state `S` is exited
+#[derive(Debug, Clone)]
+pub struct StateScoped<S: States>(pub S);
+
+/// Run condition that is `true` while `S` equals `state`
+pub fn in_state<S: States>(state: S) -> impl FnMut(Res<State<S>>) -> bool {
+    move |current: Res<State<S>>| *current == state
+}
+
+/// Runs [`OnEnter`] for the current state
+pub fn run_enter_schedule<S: States>(world: &mut World) {
+    let state = world.resource::<State<S>>().0.clone();
+    let _ = world.try_run_schedule(OnEnter(state));
+}
+
+/// Moves `S` to the value requested in [`NextState`], running [`OnExit`], despawning the
+/// entities scoped to the old state, then running [`OnTransition`] and [`OnEnter`]
+pub fn apply_state_transition<S: States>(world: &mut World) {
+    let Some(entered) = world.resource_mut::<NextState<S>>().0.take() else {
+        return;
+    };
+    if world.resource::<State<S>>().0 == entered {
+        return;
+    }
+    let exited = mem::replace(&mut world.resource_mut::<State<S>>().0, entered.clone());
+
+    let _ = world.try_run_schedule(OnExit(exited.clone()));
+    despawn_state_scoped(world, &exited);
+    let _ = world.try_run_schedule(OnTransition {
+        from: exited,
+        to: entered.clone(),
+    });
+    let _ = world.try_run_schedule(OnEnter(entered));
+}
+
+fn despawn_state_scoped<S: States>(world: &mut World, exited: &S) {
+    let scoped: Vec<Entity> = {
+        let storage = world.get::<VecStorage<StateScoped<S>>>();
+        world
+            .entities()
+            .filter(|entity| {
+                storage
+                    .get(entity.index())
+                    .is_some_and(|scope| scope.0 == *exited)
+            })
+            .collect()
+    };
+    for entity in scoped {
+        world.despawn(entity);
+    }
+}
+
+impl App {
+    /// Registers the state `S`, starting at its default value
+    ///
+    /// [`OnEnter`] of the initial state runs during the first [`StateTransition`].
+    pub fn add_state<S: States>(&mut self) -> &mut Self {
+        if self.world.contains_resource::<State<S>>() {
+            return self;
+        }
+        self.init_resource::<State<S>>()
+            .init_resource::<NextState<S>>();
+        self.world.register::<VecStorage<StateScoped<S>>>();
+        self.add_systems(
+            StateTransition,
+            (
+                run_enter_schedule::<S>.run_if(run_once()),
+                apply_state_transition::<S>,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Main, Update};
+    use komorebi_ecs::prelude::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    enum GameState {
+        #[default]
+        Menu,
+        Playing,
+    }
+    impl States for GameState {}
+
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    fn log(message: &'static str) -> impl FnMut(ResMut<Log>) {
+        move |mut log: ResMut<Log>| log.0.push(message.to_string())
+    }
+
+    #[test]
+    fn transitions() {
+        let mut app = App::new();
+        app.add_state::<GameState>()
+            .init_resource::<Log>()
+            .add_systems(OnEnter(GameState::Menu), log("enter menu"))
+            .add_systems(OnExit(GameState::Menu), log("exit menu"))
+            .add_systems(
+                OnTransition {
+                    from: GameState::Menu,
+                    to: GameState::Playing,
+                },
+                log("menu -> playing"),
+            )
+            .add_systems(OnEnter(GameState::Playing), log("enter playing"))
+            .add_systems(Update, log("play").run_if(in_state(GameState::Playing)));
+
+        app.world.run_schedule(Main);
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        app.world.run_schedule(Main);
+        app.world.run_schedule(Main);
+
+        assert_eq!(
+            *app.world.resource::<State<GameState>>(),
+            GameState::Playing
+        );
+        assert_eq!(
+            app.world.resource::<Log>().0,
+            [
+                "enter menu",
+                "exit menu",
+                "menu -> playing",
+                "enter playing",
+                "play",
+                "play"
+            ]
+        );
+    }
+
+    #[test]
+    fn state_scoped_entities() {
+        let mut app = App::new();
+        app.add_state::<GameState>();
+        let menu = app.world.spawn();
+        let playing = app.world.spawn();
+        let unscoped = app.world.spawn();
+        app.world
+            .insert::<VecStorage<StateScoped<GameState>>>(menu, StateScoped(GameState::Menu));
+        app.world
+            .insert::<VecStorage<StateScoped<GameState>>>(playing, StateScoped(GameState::Playing));
+
+        app.add_systems(Update, |mut next_state: ResMut<NextState<GameState>>| {
+            next_state.set(GameState::Playing)
+        });
+        app.world.run_schedule(Main);
+        assert!(app.world.contains(menu));
+        app.world.run_schedule(Main);
+        assert!(!app.world.contains(menu));
+        assert!(app.world.contains(playing));
+        assert!(app.world.contains(unscoped));
+    }
+}
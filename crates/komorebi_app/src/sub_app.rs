@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use komorebi_ecs::{define_label, World};
+
+use crate::App;
+
+define_label!(
+    /// A label identifying a [`SubApp`] of an [`App`]
+    AppLabel
+);
+
+type ExtractFn = Box<dyn Fn(&mut World, &mut App)>;
+
+/// A child [`App`] with its own [`World`] and schedules, updated right after its parent
+///
+/// Each frame the extract function first copies what the sub-app needs from the parent world,
+/// then the sub-app runs its [main schedule](App::set_main_schedule_label). This lets, for
+/// example, the renderer keep its resources apart from gameplay state.
+pub struct SubApp {
+    pub app: App,
+    extract: ExtractFn,
+}
+
+impl SubApp {
+    /// `extract` is called with the parent world and the sub-app before each update
+    pub fn new(app: App, extract: impl Fn(&mut World, &mut App) + 'static) -> Self {
+        Self {
+            app,
+            extract: Box::new(extract),
+        }
+    }
+
+    /// Copy what the sub-app needs from the parent world
+    pub fn extract(&mut self, main_world: &mut World) {
+        (self.extract)(main_world, &mut self.app);
+    }
+
+    /// Update the sub-app, see [`App::update`]
+    pub fn run(&mut self) {
+        self.app.update();
+    }
+}
+
+impl App {
+    /// Adds a sub-app, replacing any sub-app with the same label
+    ///
+    /// Sub-apps are updated in the order they were first inserted.
+    pub fn insert_sub_app(&mut self, label: impl AppLabel, sub_app: SubApp) -> &mut Self {
+        let label: Arc<dyn AppLabel> = Arc::new(label);
+        match self
+            .sub_apps
+            .iter_mut()
+            .find(|(other, _)| **other == *label)
+        {
+            Some((_, existing)) => *existing = sub_app,
+            None => self.sub_apps.push((label, sub_app)),
+        }
+        self
+    }
+
+    pub fn remove_sub_app(&mut self, label: impl AppLabel) -> Option<SubApp> {
+        let label: &dyn AppLabel = &label;
+        let index = self
+            .sub_apps
+            .iter()
+            .position(|(other, _)| &**other == label)?;
+        Some(self.sub_apps.remove(index).1)
+    }
+
+    pub fn get_sub_app(&self, label: impl AppLabel) -> Option<&App> {
+        let label: &dyn AppLabel = &label;
+        self.sub_apps
+            .iter()
+            .find(|(other, _)| &**other == label)
+            .map(|(_, sub_app)| &sub_app.app)
+    }
+
+    pub fn get_sub_app_mut(&mut self, label: impl AppLabel) -> Option<&mut App> {
+        let label: &dyn AppLabel = &label;
+        self.sub_apps
+            .iter_mut()
+            .find(|(other, _)| &**other == label)
+            .map(|(_, sub_app)| &mut sub_app.app)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the sub-app does not exist.
+    pub fn sub_app(&self, label: impl AppLabel) -> &App {
+        let name = format!("{label:?}");
+        self.get_sub_app(label)
+            .unwrap_or_else(|| panic!("sub-app {name} does not exist"))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the sub-app does not exist.
+    pub fn sub_app_mut(&mut self, label: impl AppLabel) -> &mut App {
+        let name = format!("{label:?}");
+        self.get_sub_app_mut(label)
+            .unwrap_or_else(|| panic!("sub-app {name} does not exist"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::{AppLabel, SubApp};
+    use komorebi_ecs::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct ChildApp;
+    impl AppLabel for ChildApp {}
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct ChildSchedule;
+    impl ScheduleLabel for ChildSchedule {}
+
+    #[derive(Default)]
+    struct Counter(u32);
+
+    #[derive(Default)]
+    struct Extracted(Vec<u32>);
+
+    #[test]
+    fn extract_then_run() {
+        let mut child = App::empty();
+        child
+            .init_resource::<Extracted>()
+            .set_main_schedule_label(ChildSchedule)
+            .add_systems(ChildSchedule, |mut extracted: ResMut<Extracted>| {
+                extracted.0.push(0);
+            });
+
+        let mut app = App::new();
+        app.init_resource::<Counter>()
+            .add_systems(Update, |mut counter: ResMut<Counter>| counter.0 += 1)
+            .insert_sub_app(
+                ChildApp,
+                SubApp::new(child, |main_world, child| {
+                    let counter = main_world.resource::<Counter>().0;
+                    child.world.resource_mut::<Extracted>().0.push(counter);
+                }),
+            );
+        app.update();
+        app.update();
+
+        // The parent runs first, then extraction, then the child's schedule
+        assert_eq!(
+            app.sub_app(ChildApp).world.resource::<Extracted>().0,
+            [1, 0, 2, 0]
+        );
+        assert!(!app.sub_app(ChildApp).world.contains_resource::<Counter>());
+        assert!(app.remove_sub_app(ChildApp).is_some());
+        assert!(app.get_sub_app(ChildApp).is_none());
+    }
+}
@@ -0,0 +1,457 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use toml::{Table, Value};
+
+/// Where a config value came from, reported in [`ConfigError`]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    File(PathBuf),
+    /// An environment variable, see [`Config::merge_env`]
+    Env(String),
+    /// A command-line flag, see [`Config::merge_args`]
+    Arg(String),
+    /// Defaults set in code
+    Code,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::File(path) => write!(f, "file {}", path.display()),
+            ConfigSource::Env(var) => write!(f, "environment variable {var}"),
+            ConfigSource::Arg(arg) => write!(f, "argument {arg}"),
+            ConfigSource::Code => write!(f, "code"),
+        }
+    }
+}
+
+/// Error returned when config cannot be loaded or does not match the settings reading it
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A config file is not valid TOML or RON
+    Parse { path: PathBuf, message: String },
+    /// A config file is neither `.toml` nor `.ron`
+    UnsupportedFormat { path: PathBuf },
+    /// A command-line flag is missing its value
+    MissingValue { arg: String },
+    /// No settings use this key
+    UnknownKey { key: String, source: ConfigSource },
+    /// The value of this key does not have the type of the settings field
+    InvalidValue {
+        key: String,
+        source: ConfigSource,
+        message: String,
+    },
+    /// The defaults of a section do not serialize to a table of keys
+    InvalidDefaults { section: String, message: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "cannot read config file {}: {source}", path.display())
+            }
+            ConfigError::Parse { path, message } => {
+                write!(f, "cannot parse config file {}: {message}", path.display())
+            }
+            ConfigError::UnsupportedFormat { path } => write!(
+                f,
+                "config file {} must have a .toml or .ron extension",
+                path.display()
+            ),
+            ConfigError::MissingValue { arg } => write!(f, "missing value for argument {arg}"),
+            ConfigError::UnknownKey { key, source } => {
+                write!(f, "unknown config key {key} (set by {source})")
+            }
+            ConfigError::InvalidValue {
+                key,
+                source,
+                message,
+            } => write!(f, "invalid config key {key} (set by {source}): {message}"),
+            ConfigError::InvalidDefaults { section, message } => write!(
+                f,
+                "cannot represent the defaults of config section {section}: {message}"
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Settings merged from config files, environment variables and command-line flags
+///
+/// Values are organized in sections, one per [`Settings`](crate::Settings) type. Each layer
+/// overrides the keys set by the previous ones, so files are usually merged first, then the
+/// environment, then the command line.
+#[derive(Debug, Default)]
+pub struct Config {
+    values: Table,
+    /// Layer that set each dotted key
+    sources: HashMap<String, ConfigSource>,
+    read_sections: Mutex<HashSet<String>>,
+    /// Incremented each time the config is reloaded, see [`ConfigPlugin::watch_for_changes`]
+    ///
+    /// [`ConfigPlugin::watch_for_changes`]: crate::ConfigPlugin::watch_for_changes
+    pub(crate) generation: u64,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges a TOML or RON file, depending on its extension
+    pub fn merge_file(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |message: String| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message,
+        };
+        let table: Table = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&text).map_err(|e| parse_error(e.to_string()))?,
+            // Through `ron::Value`, which reads structs like `(window: (width: 800))` as maps
+            Some("ron") => ron::from_str::<ron::Value>(&text)
+                .map_err(|e| e.to_string())
+                .and_then(|value| value.into_rust().map_err(|e| e.to_string()))
+                .map_err(parse_error)?,
+            _ => {
+                return Err(ConfigError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                })
+            }
+        };
+        let source = ConfigSource::File(path.to_path_buf());
+        merge_table(&mut self.values, table, "", &mut |key| {
+            self.sources.insert(key, source.clone());
+        });
+        Ok(self)
+    }
+
+    /// Merges the variables named `{prefix}_{SECTION}__{KEY}`
+    ///
+    /// `__` separates nested keys, which are lowercased. Values are parsed as TOML, falling back
+    /// to strings, so `KOMOREBI_WINDOW__CLOSE_WHEN_REQUESTED=false` sets a boolean.
+    pub fn merge_env(
+        &mut self,
+        prefix: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> &mut Self {
+        let prefix = format!("{prefix}_");
+        for (var, value) in vars {
+            let Some(key) = var.strip_prefix(&prefix) else {
+                continue;
+            };
+            // Nested keys always have a section
+            if !key.contains("__") {
+                continue;
+            }
+            let key = key.to_lowercase().replace("__", ".");
+            self.set(&key, parse_value(&value), ConfigSource::Env(var));
+        }
+        self
+    }
+
+    /// Merges the `--set section.key=value` flags, ignoring other arguments
+    ///
+    /// Values are parsed as in [`Config::merge_env`].
+    pub fn merge_args(
+        &mut self,
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<&mut Self, ConfigError> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let assignment = match arg.strip_prefix("--set") {
+                Some("") => args
+                    .next()
+                    .ok_or_else(|| ConfigError::MissingValue { arg: arg.clone() })?,
+                Some(assignment) if assignment.starts_with('=') => assignment[1..].to_string(),
+                _ => continue,
+            };
+            let Some((key, value)) = assignment.split_once('=') else {
+                return Err(ConfigError::MissingValue { arg: assignment });
+            };
+            self.set(
+                key.trim(),
+                parse_value(value),
+                ConfigSource::Arg(format!("--set {assignment}")),
+            );
+        }
+        Ok(self)
+    }
+
+    /// Sets the value of a dotted key, like `window.close_when_requested`
+    pub fn set(&mut self, key: &str, value: Value, source: ConfigSource) -> &mut Self {
+        let mut table = &mut self.values;
+        let mut parts = key.split('.').peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                table.insert(part.to_string(), value);
+                break;
+            }
+            let entry = table
+                .entry(part.to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            if !entry.is_table() {
+                *entry = Value::Table(Table::new());
+            }
+            table = entry.as_table_mut().unwrap();
+        }
+        self.sources.insert(key.to_string(), source);
+        self
+    }
+
+    /// Reads a section into `defaults`, keeping the fields it does not set
+    ///
+    /// Fails on keys `T` does not have, on values of the wrong type, and when `defaults` is not
+    /// a struct or map that serializes to a table.
+    pub fn read_section<T: Serialize + DeserializeOwned>(
+        &self,
+        section: &str,
+        defaults: T,
+    ) -> Result<T, ConfigError> {
+        self.read_sections
+            .lock()
+            .unwrap()
+            .insert(section.to_string());
+        let mut table = match Value::try_from(defaults) {
+            Ok(Value::Table(table)) => table,
+            Ok(value) => Err(ConfigError::InvalidDefaults {
+                section: section.to_string(),
+                message: format!("expected a table, found a {}", value.type_str()),
+            })?,
+            Err(e) => Err(ConfigError::InvalidDefaults {
+                section: section.to_string(),
+                message: e.to_string(),
+            })?,
+        };
+        if let Some(values) = self.values.get(section).and_then(Value::as_table) {
+            merge_table(&mut table, values.clone(), "", &mut |_| {});
+        }
+
+        let mut unknown = None;
+        let mut on_ignored = |path: serde_ignored::Path| {
+            unknown.get_or_insert_with(|| path.to_string());
+        };
+        let deserializer = serde_ignored::Deserializer::new(Value::Table(table), &mut on_ignored);
+        let value = serde_path_to_error::deserialize(deserializer).map_err(|e| {
+            let key = format!("{section}.{}", e.path());
+            ConfigError::InvalidValue {
+                source: self.source_of(&key),
+                key,
+                message: e.into_inner().to_string(),
+            }
+        })?;
+        match unknown {
+            Some(path) => {
+                let key = format!("{section}.{path}");
+                Err(ConfigError::UnknownKey {
+                    source: self.source_of(&key),
+                    key,
+                })
+            }
+            None => Ok(value),
+        }
+    }
+
+    /// Fails on the first section not read by [`Config::read_section`]
+    pub fn check_unread(&self) -> Result<(), ConfigError> {
+        let read_sections = self.read_sections.lock().unwrap();
+        match self
+            .values
+            .keys()
+            .find(|section| !read_sections.contains(*section))
+        {
+            Some(section) => Err(ConfigError::UnknownKey {
+                source: self.source_of(section),
+                key: section.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// The layer that set `key` or one of its children
+    fn source_of(&self, key: &str) -> ConfigSource {
+        let mut key = key;
+        loop {
+            if let Some(source) = self.sources.get(key) {
+                return source.clone();
+            }
+            let child = self
+                .sources
+                .iter()
+                .find(|(other, _)| other.starts_with(key) && other[key.len()..].starts_with('.'));
+            if let Some((_, source)) = child {
+                return source.clone();
+            }
+            match key.rsplit_once('.') {
+                Some((parent, _)) => key = parent,
+                None => return ConfigSource::Code,
+            }
+        }
+    }
+}
+
+/// Parses a TOML value, or a string if it is not one
+fn parse_value(value: &str) -> Value {
+    format!("value = {value}")
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+/// Merges `from` into `into`, calling `on_set` with the dotted key of every value set
+fn merge_table(into: &mut Table, from: Table, prefix: &str, on_set: &mut impl FnMut(String)) {
+    for (key, value) in from {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (into.get_mut(&key), value) {
+            (Some(Value::Table(into)), Value::Table(from)) => {
+                merge_table(into, from, &path, on_set)
+            }
+            (_, value) => {
+                if let Value::Table(from) = &value {
+                    for child in from.keys() {
+                        on_set(format!("{path}.{child}"));
+                    }
+                }
+                into.insert(key, value);
+                on_set(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Window {
+        title: String,
+        width: u32,
+        vsync: bool,
+    }
+
+    fn defaults() -> Window {
+        Window {
+            title: "komorebi".to_string(),
+            width: 800,
+            vsync: true,
+        }
+    }
+
+    fn write_file(dir: &std::path::Path, name: &str, text: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn layers_override_in_order() {
+        let dir = test_dir("layers_override_in_order");
+        let toml = write_file(
+            &dir,
+            "layers.toml",
+            "[window]\ntitle = \"file\"\nwidth = 1024\n",
+        );
+        let ron = write_file(&dir, "layers.ron", "(window: (width: 1280))");
+        let mut config = Config::new();
+        config.merge_file(&toml).unwrap().merge_file(&ron).unwrap();
+        config.merge_env(
+            "KOMOREBI",
+            [
+                ("KOMOREBI_WINDOW__VSYNC".to_string(), "false".to_string()),
+                ("KOMOREBI_WINDOW__TITLE".to_string(), "env".to_string()),
+                ("OTHER_WINDOW__WIDTH".to_string(), "1".to_string()),
+            ],
+        );
+        config
+            .merge_args(["game", "--set", "window.title=\"cli\"", "--fullscreen"].map(String::from))
+            .unwrap();
+
+        assert_eq!(
+            config.read_section("window", defaults()).unwrap(),
+            Window {
+                title: "cli".to_string(),
+                width: 1280,
+                vsync: false,
+            }
+        );
+        assert!(config.check_unread().is_ok());
+        assert_eq!(
+            Config::new().read_section("window", defaults()).unwrap(),
+            defaults()
+        );
+    }
+
+    #[test]
+    fn errors_name_key_and_source() {
+        let mut config = Config::new();
+        config
+            .merge_args(["--set=window.widht=1024".to_string()])
+            .unwrap();
+        let error = config.read_section("window", defaults()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown config key window.widht (set by argument --set window.widht=1024)"
+        );
+
+        let mut config = Config::new();
+        config.merge_env(
+            "KOMOREBI",
+            [("KOMOREBI_WINDOW__WIDTH".to_string(), "wide".to_string())],
+        );
+        let error = config.read_section("window", defaults()).unwrap_err();
+        assert!(matches!(
+            &error,
+            ConfigError::InvalidValue { key, source: ConfigSource::Env(var), .. }
+                if key == "window.width" && var == "KOMOREBI_WINDOW__WIDTH"
+        ));
+
+        let path = write_file(
+            &test_dir("errors_name_key_and_source"),
+            "unread.toml",
+            "[audio]\nvolume = 1.0\n",
+        );
+        let mut config = Config::new();
+        config.merge_file(&path).unwrap();
+        assert!(matches!(
+            config.check_unread(),
+            Err(ConfigError::UnknownKey { key, source: ConfigSource::File(_) }) if key == "audio"
+        ));
+
+        let error = Config::new().read_section("volume", 1.0).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot represent the defaults of config section volume: expected a table, found a float"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+mod config;
+mod plugin;
+mod watch;
+
+pub use config::*;
+pub use plugin::*;
+pub use watch::*;
+
+/// A directory for the files of one test, as tests run in parallel
+#[cfg(test)]
+pub(crate) fn test_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("komorebi_config_{}_{test}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{ConfigChanged, ConfigPlugin, Settings, SettingsAppExt};
+}
@@ -0,0 +1,284 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use komorebi_app::{App, BoxedError, Plugin, PreUpdate};
+use komorebi_ecs::IntoSystemConfigs;
+use komorebi_utils::tracing::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    reload_config, update_settings, Config, ConfigChanged, ConfigError, ConfigWatcher,
+    SettingsDefaults,
+};
+
+/// Loads the [`Config`] resource, merging in order:
+///
+/// - the config file, given by `--config <path>`, then `{env_prefix}_CONFIG`, then
+///   [`ConfigPlugin::file`]
+/// - the `{env_prefix}_*` environment variables, see [`Config::merge_env`]
+/// - the `--set` command-line flags, see [`Config::merge_args`]
+///
+/// Add it before the plugins reading [`Settings`]. Once every plugin is built, a warning is
+/// logged for the config sections that no plugin read, as they are likely misspelled.
+#[derive(Clone)]
+pub struct ConfigPlugin {
+    pub file: Option<PathBuf>,
+    pub env_prefix: String,
+    /// Read the environment variables and command line of the process
+    pub read_process: bool,
+    /// Check the config file for changes this often, reloading the config and the settings
+    /// added with [`SettingsAppExt::init_settings`]
+    ///
+    /// Errors in the changed file are logged, and the previous config is kept, including when
+    /// a section no longer deserializes into its settings.
+    ///
+    /// The file is read again on each check rather than watched with OS notifications: reading a
+    /// small file is cheap, needs no extra thread, and behaves the same on every platform,
+    /// network drives and editors replacing the file included. An interval of a second or so
+    /// keeps edits feeling immediate.
+    pub watch_for_changes: Option<Duration>,
+}
+
+impl Default for ConfigPlugin {
+    fn default() -> Self {
+        Self {
+            file: None,
+            env_prefix: "KOMOREBI".to_string(),
+            read_process: true,
+            watch_for_changes: None,
+        }
+    }
+}
+
+impl ConfigPlugin {
+    /// The config file, which the command line and environment may override
+    fn file(&self) -> Result<Option<PathBuf>, ConfigError> {
+        let mut file = self.file.clone();
+        if self.read_process {
+            if let Ok(path) = std::env::var(format!("{}_CONFIG", self.env_prefix)) {
+                file = Some(path.into());
+            }
+            let mut args = std::env::args();
+            while let Some(arg) = args.next() {
+                if arg == "--config" {
+                    let path = args.next().ok_or(ConfigError::MissingValue { arg })?;
+                    file = Some(path.into());
+                } else if let Some(path) = arg.strip_prefix("--config=") {
+                    file = Some(path.into());
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    pub(crate) fn load(&self) -> Result<Config, ConfigError> {
+        let mut config = Config::new();
+        if let Some(file) = self.file()? {
+            config.merge_file(file)?;
+        }
+        if self.read_process {
+            config
+                .merge_env(&self.env_prefix, std::env::vars())
+                .merge_args(std::env::args())?;
+        }
+        Ok(config)
+    }
+}
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        self.try_build(app).unwrap();
+    }
+
+    fn try_build(&self, app: &mut App) -> Result<(), BoxedError> {
+        app.insert_resource(self.load()?);
+        if let (Some(interval), Some(path)) = (self.watch_for_changes, self.file()?) {
+            app.insert_resource(ConfigWatcher {
+                plugin: self.clone(),
+                contents: std::fs::read_to_string(&path).ok(),
+                path,
+                interval,
+                last_check: Instant::now(),
+                validators: Vec::new(),
+            })
+            .add_systems(PreUpdate, reload_config);
+        }
+        Ok(())
+    }
+
+    /// Warns about config sections no plugin read
+    fn cleanup(&self, app: &mut App) {
+        if let Err(e) = app.world.resource::<Config>().check_unread() {
+            warn!("{e}");
+        }
+    }
+}
+
+/// Plugin settings read from a [`Config`] section
+pub trait Settings: Serialize + DeserializeOwned {
+    /// Name of the section, like `window`
+    const SECTION: &'static str;
+}
+
+/// Reads [`Settings`] from the [`Config`] resource
+pub trait SettingsAppExt {
+    /// Overrides the fields of `defaults` set in the config, usually from [`Plugin::build`]
+    ///
+    /// Returns `defaults` without a [`Config`] resource.
+    fn read_settings<S: Settings>(&self, defaults: S) -> Result<S, ConfigError>;
+
+    /// Inserts the settings read by [`SettingsAppExt::read_settings`] as a resource
+    ///
+    /// When the config is [reloaded](ConfigPlugin::watch_for_changes), the resource is updated
+    /// and a [`ConfigChanged`] event is sent.
+    fn init_settings<S: Settings + Clone + Send + 'static>(
+        &mut self,
+        defaults: S,
+    ) -> Result<&mut Self, ConfigError>;
+}
+
+impl SettingsAppExt for App {
+    fn read_settings<S: Settings>(&self, defaults: S) -> Result<S, ConfigError> {
+        match self.world.get_resource::<Config>() {
+            Some(config) => config.read_section(S::SECTION, defaults),
+            None => Ok(defaults),
+        }
+    }
+
+    fn init_settings<S: Settings + Clone + Send + 'static>(
+        &mut self,
+        defaults: S,
+    ) -> Result<&mut Self, ConfigError> {
+        let settings = self.read_settings(defaults.clone())?;
+        self.insert_resource(settings)
+            .add_event::<ConfigChanged<S>>();
+        let watched = match self.world.get_resource_mut::<ConfigWatcher>() {
+            Some(mut watcher) => {
+                let defaults = defaults.clone();
+                watcher.validators.push(Box::new(move |config| {
+                    config.read_section(S::SECTION, defaults.clone()).map(drop)
+                }));
+                true
+            }
+            None => false,
+        };
+        if watched {
+            self.insert_resource(SettingsDefaults(defaults))
+                .add_systems(PreUpdate, update_settings::<S>.after(reload_config));
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use komorebi_app::prelude::*;
+    use komorebi_ecs::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    struct AudioSettings {
+        volume: f32,
+        muted: bool,
+    }
+
+    impl Default for AudioSettings {
+        fn default() -> Self {
+            Self {
+                volume: 1.0,
+                muted: false,
+            }
+        }
+    }
+
+    impl Settings for AudioSettings {
+        const SECTION: &'static str = "audio";
+    }
+
+    struct AudioPlugin;
+    impl Plugin for AudioPlugin {
+        fn build(&self, app: &mut App) {
+            let settings = app.read_settings(AudioSettings::default()).unwrap();
+            app.insert_resource(settings);
+        }
+    }
+
+    #[test]
+    fn plugins_read_settings() {
+        let path = test_dir("plugins_read_settings").join("config.toml");
+        std::fs::write(&path, "[audio]\nvolume = 0.5\n[unknown]\nkey = 1\n").unwrap();
+        let mut app = App::new();
+        app.add_plugins(ConfigPlugin {
+            file: Some(path),
+            read_process: false,
+            ..Default::default()
+        })
+        .add_plugins(AudioPlugin);
+        let settings = app.world.resource::<AudioSettings>();
+        assert_eq!(settings.volume, 0.5);
+        assert!(!settings.muted);
+        drop(settings);
+        // The unread section is only a warning on cleanup
+        app.step_n(1);
+    }
+
+    #[test]
+    fn hot_reload() {
+        let path = test_dir("hot_reload").join("config.toml");
+        std::fs::write(&path, "[audio]\nvolume = 0.5\n").unwrap();
+        let mut app = App::new();
+        app.add_plugins(ConfigPlugin {
+            file: Some(path.clone()),
+            read_process: false,
+            watch_for_changes: Some(std::time::Duration::ZERO),
+            ..Default::default()
+        })
+        .init_settings(AudioSettings::default())
+        .unwrap();
+        let mut reader = app
+            .world
+            .resource::<Events<ConfigChanged<AudioSettings>>>()
+            .get_reader();
+        app.update();
+
+        std::fs::write(&path, "[audio]\nvolume = 0.25\n").unwrap();
+        app.update();
+        assert_eq!(app.world.resource::<AudioSettings>().volume, 0.25);
+        let events = app.world.resource::<Events<ConfigChanged<AudioSettings>>>();
+        let changed: Vec<_> = reader.read(&events).map(|e| e.settings.volume).collect();
+        assert_eq!(changed, [0.25]);
+        drop(events);
+
+        // Errors are logged, keeping the previous settings
+        std::fs::write(&path, "[audio]\nvolume = \"loud\"\n").unwrap();
+        app.update();
+        let config = app.world.resource::<Config>();
+        let kept = config.read_section(AudioSettings::SECTION, AudioSettings::default());
+        assert_eq!(kept.unwrap().volume, 0.25);
+        drop(config);
+        std::fs::write(&path, "[audio\n").unwrap();
+        app.update();
+        assert_eq!(app.world.resource::<AudioSettings>().volume, 0.25);
+        let events = app.world.resource::<Events<ConfigChanged<AudioSettings>>>();
+        assert!(reader.is_empty(&events));
+    }
+
+    #[test]
+    fn missing_file() {
+        let error = App::new()
+            .try_add_plugins(ConfigPlugin {
+                file: Some("missing.toml".into()),
+                read_process: false,
+                ..Default::default()
+            })
+            .err()
+            .unwrap();
+        assert!(error
+            .to_string()
+            .contains("cannot read config file missing.toml"));
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use komorebi_ecs::{EventWriter, Local, Res, ResMut};
+use komorebi_utils::tracing::{error, info};
+
+use crate::{Config, ConfigError, ConfigPlugin, Settings};
+
+/// Sent when reloading the config changed the settings of type `S`
+///
+/// The `S` resource holds the same settings.
+#[derive(Debug, Clone)]
+pub struct ConfigChanged<S> {
+    pub settings: S,
+}
+
+/// Checks that a reloaded config can still be read into the settings of one type
+pub(crate) type SettingsValidator = Box<dyn Fn(&Config) -> Result<(), ConfigError> + Send>;
+
+/// Polls the config file of a [`ConfigPlugin`], see [`ConfigPlugin::watch_for_changes`]
+pub(crate) struct ConfigWatcher {
+    pub(crate) plugin: ConfigPlugin,
+    pub(crate) path: PathBuf,
+    pub(crate) interval: Duration,
+    pub(crate) last_check: Instant,
+    /// `None` while the file cannot be read
+    pub(crate) contents: Option<String>,
+    /// One per type added with [`SettingsAppExt::init_settings`](crate::SettingsAppExt)
+    pub(crate) validators: Vec<SettingsValidator>,
+}
+
+/// Reloads every layer of the config when its file changes, keeping the old config on errors
+pub(crate) fn reload_config(mut watcher: ResMut<ConfigWatcher>, mut config: ResMut<Config>) {
+    if watcher.last_check.elapsed() < watcher.interval {
+        return;
+    }
+    watcher.last_check = Instant::now();
+    let contents = match fs::read_to_string(&watcher.path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            if watcher.contents.take().is_some() {
+                error!("cannot read config file {}: {e}", watcher.path.display());
+            }
+            return;
+        }
+    };
+    if watcher.contents.as_ref() == Some(&contents) {
+        return;
+    }
+    watcher.contents = Some(contents);
+    let reloaded = watcher.plugin.load().and_then(|reloaded| {
+        // Checked before replacing the config, so that no settings are left half reloaded
+        for validate in &watcher.validators {
+            validate(&reloaded)?;
+        }
+        Ok(reloaded)
+    });
+    match reloaded {
+        Ok(mut reloaded) => {
+            reloaded.generation = config.generation + 1;
+            *config = reloaded;
+            info!("reloaded config file {}", watcher.path.display());
+        }
+        Err(e) => error!("{e}"),
+    }
+}
+
+/// The defaults that [`update_settings`] reads the config into
+pub(crate) struct SettingsDefaults<S>(pub(crate) S);
+
+/// Reads the settings again after the config was reloaded, sending [`ConfigChanged`] if they
+/// changed
+pub(crate) fn update_settings<S: Settings + Clone + Send + 'static>(
+    config: Res<Config>,
+    defaults: Res<SettingsDefaults<S>>,
+    mut settings: ResMut<S>,
+    mut changed: EventWriter<ConfigChanged<S>>,
+    mut generation: Local<u64>,
+) {
+    if config.generation == *generation {
+        return;
+    }
+    *generation = config.generation;
+    match config.read_section(S::SECTION, defaults.0.clone()) {
+        Ok(reloaded) => {
+            // Compared as config values, so that settings need not implement `PartialEq`
+            if toml::Value::try_from(&reloaded).ok() != toml::Value::try_from(&*settings).ok() {
+                *settings = reloaded.clone();
+                changed.send(ConfigChanged { settings: reloaded });
+            }
+        }
+        Err(e) => error!("cannot reload settings: {e}"),
+    }
+}
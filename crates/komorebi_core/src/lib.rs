@@ -1,3 +1,17 @@
+mod task;
+mod task_pool;
+mod task_pool_plugin;
+mod time;
+
+pub use task::*;
+pub use task_pool::*;
+pub use task_pool_plugin::*;
+pub use time::*;
+
 pub mod prelude {
-    // pub use crate::{app::App, window::Window};
+    #[doc(hidden)]
+    pub use crate::{
+        AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool, Stopwatch, Task, TaskPoolPlugin, Time,
+        TimePlugin, TimeUpdateStrategy, Timer, TimerMode,
+    };
 }
@@ -0,0 +1,118 @@
+use std::{
+    any::Any,
+    future::Future,
+    panic::resume_unwind,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+struct TaskState<T> {
+    /// The output, or the panic of the task
+    result: Option<thread::Result<T>>,
+    /// Set when the result was taken
+    taken: bool,
+    waker: Option<Waker>,
+}
+
+pub(crate) struct TaskShared<T> {
+    state: Mutex<TaskState<T>>,
+    finished: Condvar,
+}
+
+impl<T> TaskShared<T> {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(TaskState {
+                result: None,
+                taken: false,
+                waker: None,
+            }),
+            finished: Condvar::new(),
+        })
+    }
+
+    pub(crate) fn finish(&self, result: Result<T, Box<dyn Any + Send>>) {
+        let mut state = self.state.lock().unwrap();
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.finished.notify_all();
+    }
+}
+
+/// Handle to a task spawned on a [`TaskPool`](crate::TaskPool)
+///
+/// Systems can check it every frame with [`Task::try_result`], and async code can `.await` it.
+/// Dropping the handle detaches the task, which keeps running.
+///
+/// If the task panicked, taking its result resumes the panic.
+pub struct Task<T>(pub(crate) Arc<TaskShared<T>>);
+
+impl<T> Task<T> {
+    pub fn is_finished(&self) -> bool {
+        self.0.state.lock().unwrap().result.is_some()
+    }
+
+    /// Takes the output if the task is finished
+    ///
+    /// # Panics
+    ///
+    /// Panics if the output was already taken.
+    pub fn try_result(&mut self) -> Option<T> {
+        let mut state = self.0.state.lock().unwrap();
+        assert!(!state.taken, "the task output was already taken");
+        let result = state.result.take()?;
+        state.taken = true;
+        drop(state);
+        Some(result.unwrap_or_else(|panic| resume_unwind(panic)))
+    }
+
+    /// Blocks the current thread until the task is finished
+    pub fn block(mut self) -> T {
+        let mut state = self.0.state.lock().unwrap();
+        while state.result.is_none() {
+            state = self.0.finished.wait(state).unwrap();
+        }
+        drop(state);
+        self.try_result().unwrap()
+    }
+}
+
+impl<T> Future for Task<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        {
+            let mut state = self.0.state.lock().unwrap();
+            if state.result.is_none() {
+                state.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+        Poll::Ready(self.try_result().unwrap())
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread
+pub fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
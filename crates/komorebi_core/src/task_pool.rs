@@ -0,0 +1,462 @@
+use std::{
+    any::Any,
+    future::Future,
+    marker::PhantomData,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use crossbeam_deque::{Injector, Steal, Stealer, Worker};
+
+use crate::{block_on, Task, TaskShared};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// The queues of a [`TaskPool`], shared with its threads
+///
+/// Jobs are pushed to the injector. Each thread takes them in batches into its own deque, from
+/// which idle threads steal.
+struct PoolShared {
+    injector: Injector<Job>,
+    stealers: Vec<Stealer<Job>>,
+    /// Whether the pool was dropped, locked by threads before going to sleep
+    shutdown: Mutex<bool>,
+    /// Notified when jobs are queued or the pool is dropped
+    work: Condvar,
+}
+
+impl PoolShared {
+    fn push(&self, job: Job) {
+        self.injector.push(job);
+        self.wake_one();
+    }
+
+    fn wake_one(&self) {
+        // Locking makes sure a thread checking for work before sleeping sees the job, or is
+        // already waiting and gets notified
+        drop(self.shutdown.lock().unwrap());
+        self.work.notify_one();
+    }
+
+    fn has_work(&self) -> bool {
+        !self.injector.is_empty() || self.stealers.iter().any(|stealer| !stealer.is_empty())
+    }
+
+    /// Takes a job from `local`, the injector, or the other threads
+    fn find_job(&self, local: &Worker<Job>) -> Option<Job> {
+        if let Some(job) = local.pop() {
+            return Some(job);
+        }
+        loop {
+            let steal = self
+                .injector
+                .steal_batch_and_pop(local)
+                .or_else(|| self.stealers.iter().map(Stealer::steal).collect());
+            match steal {
+                Steal::Success(job) => {
+                    // Let a sleeping thread steal the rest of the batch
+                    if !local.is_empty() {
+                        self.wake_one();
+                    }
+                    return Some(job);
+                }
+                Steal::Empty => return None,
+                Steal::Retry => {}
+            }
+        }
+    }
+
+    fn run_thread(&self, local: Worker<Job>) {
+        loop {
+            if let Some(job) = self.find_job(&local) {
+                job();
+                continue;
+            }
+            let shutdown = self.shutdown.lock().unwrap();
+            // Queued work is finished before stopping
+            if self.has_work() {
+                continue;
+            }
+            if *shutdown {
+                break;
+            }
+            drop(self.work.wait(shutdown).unwrap());
+        }
+    }
+}
+
+/// Configures a [`TaskPool`]
+#[derive(Clone, Debug, Default)]
+pub struct TaskPoolBuilder {
+    num_threads: Option<usize>,
+    thread_name: Option<String>,
+}
+
+impl TaskPoolBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of worker threads, the available parallelism by default
+    ///
+    /// With no threads, tasks run on the thread spawning them.
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    /// Prefix of the worker thread names
+    pub fn thread_name(mut self, thread_name: impl Into<String>) -> Self {
+        self.thread_name = Some(thread_name.into());
+        self
+    }
+
+    pub fn build(self) -> TaskPool {
+        let num_threads = self.num_threads.unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        });
+        let thread_name = self.thread_name.unwrap_or_else(|| "Task Pool".to_string());
+        let locals: Vec<_> = (0..num_threads).map(|_| Worker::new_fifo()).collect();
+        let shared = Arc::new(PoolShared {
+            injector: Injector::new(),
+            stealers: locals.iter().map(Worker::stealer).collect(),
+            shutdown: Mutex::new(false),
+            work: Condvar::new(),
+        });
+        let threads = locals
+            .into_iter()
+            .enumerate()
+            .map(|(i, local)| {
+                let shared = shared.clone();
+                thread::Builder::new()
+                    .name(format!("{thread_name} ({i})"))
+                    .spawn(move || shared.run_thread(local))
+                    .expect("failed to spawn a task pool thread")
+            })
+            .collect();
+        TaskPool { shared, threads }
+    }
+}
+
+/// A pool of threads running [`Task`]s and [scoped](TaskPool::scope) work
+///
+/// Dropping the pool waits for the queued work to finish.
+pub struct TaskPool {
+    shared: Arc<PoolShared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Default for TaskPool {
+    fn default() -> Self {
+        TaskPoolBuilder::new().build()
+    }
+}
+
+impl TaskPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn thread_num(&self) -> usize {
+        self.threads.len()
+    }
+
+    fn send(&self, job: Job) {
+        if self.threads.is_empty() {
+            job();
+        } else {
+            self.shared.push(job);
+        }
+    }
+
+    /// Runs `future` on a thread of the pool
+    ///
+    /// The future occupies its thread until it completes, so pools running blocking or
+    /// long-running futures should have enough threads.
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> Task<T> {
+        let shared = TaskShared::new();
+        let task = Task(shared.clone());
+        self.send(Box::new(move || {
+            shared.finish(catch_unwind(AssertUnwindSafe(|| block_on(future))));
+        }));
+        task
+    }
+
+    /// Runs the work spawned by `f` on the pool, returning once all of it is done
+    ///
+    /// The work may borrow from the calling stack frame. The calling thread runs the work of
+    /// this scope that was not started yet while it waits, so scopes can be nested, and never
+    /// picks up unrelated tasks. Panics in the work are resumed once it is done.
+    pub fn scope<'env, R>(&self, f: impl FnOnce(&Scope<'_, 'env>) -> R) -> R {
+        let scope = Scope {
+            pool: self,
+            shared: Arc::new(ScopeShared {
+                state: Mutex::new(ScopeState {
+                    pending: 0,
+                    panic: None,
+                }),
+                done: Condvar::new(),
+                jobs: Injector::new(),
+            }),
+            marker: PhantomData,
+        };
+        // A panic in `f` is resumed below, once the jobs it spawned are done
+        let result = catch_unwind(AssertUnwindSafe(|| f(&scope)));
+
+        // Help with the jobs of this scope until every one is done, as they borrow from the stack
+        // frames of this function and its caller
+        let mut state = scope.shared.state.lock().unwrap();
+        while state.pending > 0 {
+            drop(state);
+            if let Some(job) = scope.shared.take_job() {
+                job();
+                state = scope.shared.state.lock().unwrap();
+                continue;
+            }
+            // The remaining jobs are running on other threads, the last one notifies
+            state = scope.shared.state.lock().unwrap();
+            if state.pending > 0 {
+                state = scope.shared.done.wait(state).unwrap();
+            }
+        }
+        let panic = state.panic.take();
+        drop(state);
+
+        match (result, panic) {
+            (Err(panic), _) | (Ok(_), Some(panic)) => resume_unwind(panic),
+            (Ok(result), None) => result,
+        }
+    }
+
+    /// Calls `f` on each item in parallel, in batches of about the same size per thread
+    pub fn for_each<T: Sync>(&self, items: &[T], f: impl Fn(&T) + Sync) {
+        let f = &f;
+        self.scope(|scope| {
+            for batch in items.chunks(self.batch_size(items.len())) {
+                scope.spawn(move || batch.iter().for_each(f));
+            }
+        });
+    }
+
+    /// Calls `f` on each item in parallel, in batches of about the same size per thread
+    pub fn for_each_mut<T: Send>(&self, items: &mut [T], f: impl Fn(&mut T) + Sync) {
+        let f = &f;
+        let batch_size = self.batch_size(items.len());
+        self.scope(|scope| {
+            for batch in items.chunks_mut(batch_size) {
+                scope.spawn(move || batch.iter_mut().for_each(f));
+            }
+        });
+    }
+
+    fn batch_size(&self, len: usize) -> usize {
+        // One batch more than threads, for the thread waiting on the scope
+        len.div_ceil(self.thread_num() + 1).max(1)
+    }
+}
+
+impl Drop for TaskPool {
+    fn drop(&mut self) {
+        *self.shared.shutdown.lock().unwrap() = true;
+        self.shared.work.notify_all();
+        for thread in self.threads.drain(..) {
+            // Panics are reported by the tasks
+            let _ = thread.join();
+        }
+    }
+}
+
+struct ScopeState {
+    pending: usize,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+struct ScopeShared {
+    state: Mutex<ScopeState>,
+    /// Notified by the last pending job
+    done: Condvar,
+    /// Jobs not started yet, taken by the pool threads or the thread waiting on the scope
+    jobs: Injector<Job>,
+}
+
+impl ScopeShared {
+    fn take_job(&self) -> Option<Job> {
+        loop {
+            match self.jobs.steal() {
+                Steal::Success(job) => return Some(job),
+                Steal::Empty => return None,
+                Steal::Retry => {}
+            }
+        }
+    }
+}
+
+/// Spawns work borrowing from the environment of a [`TaskPool::scope`]
+pub struct Scope<'scope, 'env: 'scope> {
+    pool: &'scope TaskPool,
+    shared: Arc<ScopeShared>,
+    /// Invariant over `'env`, like [`std::thread::Scope`]
+    marker: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    pub fn spawn(&self, f: impl FnOnce() + Send + 'env) {
+        self.shared.state.lock().unwrap().pending += 1;
+        let shared = self.shared.clone();
+        let job: Box<dyn FnOnce() + Send + 'env> = Box::new(move || {
+            let result = catch_unwind(AssertUnwindSafe(f));
+            let mut state = shared.state.lock().unwrap();
+            if let Err(panic) = result {
+                state.panic.get_or_insert(panic);
+            }
+            state.pending -= 1;
+            if state.pending == 0 {
+                shared.done.notify_all();
+            }
+        });
+        // SAFETY: The job only borrows from `'env`, which outlives the call to
+        // `TaskPool::scope` this `Scope` belongs to. `pending` was incremented above, and is
+        // only decremented once the job ran, its panics included as they are caught. `scope`
+        // catches panics from its closure too, and always waits for `pending` to reach zero
+        // before returning or resuming a panic. So the job is done with its borrows before
+        // `'env` ends, and erasing the lifetime to queue it is sound.
+        let job: Job = unsafe { std::mem::transmute(job) };
+        self.shared.jobs.push(job);
+        // The pool runs a job of the scope for each one spawned, unless the thread waiting on
+        // the scope took them all already
+        let shared = self.shared.clone();
+        self.pool.send(Box::new(move || {
+            if let Some(job) = shared.take_job() {
+                job();
+            }
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::*;
+
+    #[test]
+    fn spawn_tasks() {
+        let pool = TaskPoolBuilder::new().num_threads(2).build();
+        let mut task = pool.spawn(async { 40 + 2 });
+        let other = pool.spawn(async { "done" });
+        assert_eq!(other.block(), "done");
+        while !task.is_finished() {
+            std::thread::yield_now();
+        }
+        assert_eq!(task.try_result(), Some(42));
+
+        // Tasks can await each other
+        let first = pool.spawn(async { 1 });
+        assert_eq!(pool.spawn(async { first.await + 1 }).block(), 2);
+
+        // Without threads, tasks run inline
+        let mut inline = TaskPoolBuilder::new()
+            .num_threads(0)
+            .build()
+            .spawn(async { 3 });
+        assert_eq!(inline.try_result(), Some(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "task panic")]
+    fn task_panic() {
+        let pool = TaskPoolBuilder::new().num_threads(1).build();
+        pool.spawn(async { panic!("task panic") }).block();
+    }
+
+    #[test]
+    fn scoped_for_each() {
+        let pool = TaskPoolBuilder::new().num_threads(3).build();
+        let mut values: Vec<usize> = (0..1000).collect();
+        pool.for_each_mut(&mut values, |value| *value *= 2);
+        assert_eq!(values, (0..1000).map(|v| v * 2).collect::<Vec<_>>());
+
+        let sum = AtomicUsize::new(0);
+        pool.for_each(&values, |value| {
+            sum.fetch_add(*value, Ordering::Relaxed);
+        });
+        assert_eq!(sum.into_inner(), 999 * 1000);
+
+        // Nested scopes do not deadlock, even with every thread waiting
+        let count = AtomicUsize::new(0);
+        pool.scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    pool.for_each(&[0; 16], |_| {
+                        count.fetch_add(1, Ordering::Relaxed);
+                    })
+                });
+            }
+        });
+        assert_eq!(count.into_inner(), 8 * 16);
+    }
+
+    #[test]
+    fn scope_waits_for_jobs() {
+        // Both jobs wait for each other, so the thread waiting on the scope runs one of them
+        let pool = TaskPoolBuilder::new().num_threads(1).build();
+        let barrier = std::sync::Barrier::new(2);
+        pool.scope(|scope| {
+            scope.spawn(|| {
+                barrier.wait();
+            });
+            scope.spawn(|| {
+                barrier.wait();
+            });
+        });
+
+        // The jobs are done before the panic of the scope closure is resumed
+        let done = AtomicUsize::new(0);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            pool.scope(|scope| {
+                scope.spawn(|| {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    done.fetch_add(1, Ordering::Relaxed);
+                });
+                panic!("scope panic");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(done.into_inner(), 1);
+    }
+
+    #[test]
+    fn scope_only_runs_its_jobs() {
+        let pool = TaskPoolBuilder::new()
+            .num_threads(1)
+            .thread_name("Only")
+            .build();
+        // Keep the only thread busy, so that the thread waiting on the scope runs its job
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let busy = pool.spawn(async move {
+            started_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        started_rx.recv().unwrap();
+        // Queued before the job of the scope, but not part of it
+        let unrelated = pool.spawn(async { std::thread::current().name().map(String::from) });
+        let ran = AtomicUsize::new(0);
+        pool.scope(|scope| {
+            scope.spawn(|| {
+                ran.fetch_add(1, Ordering::Relaxed);
+            });
+        });
+        assert_eq!(ran.into_inner(), 1);
+
+        release_tx.send(()).unwrap();
+        busy.block();
+        assert_eq!(unrelated.block().as_deref(), Some("Only (0)"));
+    }
+}
@@ -0,0 +1,86 @@
+use std::ops::Deref;
+
+use komorebi_app::{prelude::*, BoxedError};
+use komorebi_config::{Settings, SettingsAppExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{TaskPool, TaskPoolBuilder};
+
+/// Pool for frame-bound CPU work, like the [scoped](TaskPool::scope) work of systems
+pub struct ComputeTaskPool(pub TaskPool);
+
+/// Pool for CPU work that may take several frames, like pathfinding
+pub struct AsyncComputeTaskPool(pub TaskPool);
+
+/// Pool for work waiting on IO, like asset loading
+pub struct IoTaskPool(pub TaskPool);
+
+macro_rules! impl_deref_task_pool {
+    ($($pool:ty),*) => {$(
+        impl Deref for $pool {
+            type Target = TaskPool;
+
+            fn deref(&self) -> &TaskPool {
+                &self.0
+            }
+        }
+    )*};
+}
+
+impl_deref_task_pool!(ComputeTaskPool, AsyncComputeTaskPool, IoTaskPool);
+
+/// Inserts the [`ComputeTaskPool`], [`AsyncComputeTaskPool`] and [`IoTaskPool`] resources
+///
+/// Thread counts left to `None` are derived from the available parallelism: all of it for
+/// compute, and a quarter for each of the other pools. They can be set in the `task_pool`
+/// config section.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskPoolPlugin {
+    pub compute_threads: Option<usize>,
+    pub async_compute_threads: Option<usize>,
+    pub io_threads: Option<usize>,
+}
+
+impl Settings for TaskPoolPlugin {
+    const SECTION: &'static str = "task_pool";
+}
+
+impl Plugin for TaskPoolPlugin {
+    fn build(&self, app: &mut App) {
+        self.try_build(app).unwrap();
+    }
+
+    fn try_build(&self, app: &mut App) -> Result<(), BoxedError> {
+        let settings = app.read_settings(self.clone())?;
+        let parallelism = if cfg!(target_arch = "wasm32") {
+            // No threads on the web, tasks run inline
+            0
+        } else {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        };
+        let quarter = parallelism.div_ceil(4);
+        let pool = |threads: Option<usize>, default: usize, name: &str| {
+            TaskPoolBuilder::new()
+                .num_threads(threads.unwrap_or(default))
+                .thread_name(name)
+                .build()
+        };
+        app.insert_resource(ComputeTaskPool(pool(
+            settings.compute_threads,
+            parallelism,
+            "Compute Task Pool",
+        )))
+        .insert_resource(AsyncComputeTaskPool(pool(
+            settings.async_compute_threads,
+            quarter,
+            "Async Compute Task Pool",
+        )))
+        .insert_resource(IoTaskPool(pool(
+            settings.io_threads,
+            quarter,
+            "IO Task Pool",
+        )));
+        Ok(())
+    }
+}
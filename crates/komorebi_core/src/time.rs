@@ -0,0 +1,469 @@
+use std::time::{Duration, Instant};
+
+use komorebi_app::{prelude::*, FixedTime};
+use komorebi_ecs::{IntoSystemConfigs, Res, ResMut};
+
+/// Resource with the time of the current update, see [`TimePlugin`]
+///
+/// [`Time::delta`] and [`Time::elapsed`] follow a virtual clock, which can be
+/// [paused](Time::pause) or [sped up and slowed down](Time::set_relative_speed) for gameplay.
+/// The `raw_` methods follow the real clock.
+#[derive(Clone, Debug)]
+pub struct Time {
+    startup: Instant,
+    last_update: Option<Instant>,
+    raw_delta: Duration,
+    raw_elapsed: Duration,
+    delta: Duration,
+    elapsed: Duration,
+    frame_count: u64,
+    paused: bool,
+    relative_speed: f64,
+    /// Longest virtual delta of an update, so that a stall does not move gameplay too far
+    pub max_delta: Duration,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl Time {
+    pub const DEFAULT_MAX_DELTA: Duration = Duration::from_millis(250);
+
+    pub fn new(startup: Instant) -> Self {
+        Self {
+            startup,
+            last_update: None,
+            raw_delta: Duration::ZERO,
+            raw_elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            paused: false,
+            relative_speed: 1.0,
+            max_delta: Self::DEFAULT_MAX_DELTA,
+        }
+    }
+
+    /// Advance the clocks to now
+    pub fn update(&mut self) {
+        self.update_with_instant(Instant::now());
+    }
+
+    /// Advance the clocks to `instant`, to drive time by hand
+    ///
+    /// The first update has a zero delta.
+    pub fn update_with_instant(&mut self, instant: Instant) {
+        self.raw_delta = match self.last_update {
+            Some(last_update) => instant.saturating_duration_since(last_update),
+            None => Duration::ZERO,
+        };
+        self.last_update = Some(instant);
+        self.raw_elapsed += self.raw_delta;
+        self.delta = if self.paused {
+            Duration::ZERO
+        } else {
+            self.raw_delta
+                .mul_f64(self.relative_speed)
+                .min(self.max_delta)
+        };
+        self.elapsed += self.delta;
+        self.frame_count += 1;
+    }
+
+    /// Virtual time elapsed since the previous update
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Virtual time elapsed since startup
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    /// Real time elapsed since the previous update
+    pub fn raw_delta(&self) -> Duration {
+        self.raw_delta
+    }
+
+    /// Real time elapsed between startup and the last update
+    pub fn raw_elapsed(&self) -> Duration {
+        self.raw_elapsed
+    }
+
+    /// Number of updates so far
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// When the app started
+    pub fn startup(&self) -> Instant {
+        self.startup
+    }
+
+    /// When the clocks were last updated
+    pub fn last_update(&self) -> Option<Instant> {
+        self.last_update
+    }
+
+    /// Stop the virtual clock, the real clock keeps running
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// How fast the virtual clock runs compared to the real clock
+    pub fn relative_speed(&self) -> f64 {
+        self.relative_speed
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `speed` is negative or not finite.
+    pub fn set_relative_speed(&mut self, speed: f64) {
+        assert!(
+            speed.is_finite() && speed >= 0.0,
+            "the relative speed must be finite and not negative"
+        );
+        self.relative_speed = speed;
+    }
+}
+
+/// Tracks elapsed time while not paused
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stopwatch {
+    elapsed: Duration,
+    paused: bool,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `delta`, usually [`Time::delta`], unless paused
+    pub fn tick(&mut self, delta: Duration) -> &Self {
+        if !self.paused {
+            self.elapsed += delta;
+        }
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+}
+
+/// Whether a [`Timer`] starts over once finished
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimerMode {
+    #[default]
+    Once,
+    Repeating,
+}
+
+/// Counts down a duration, ticked like a [`Stopwatch`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Timer {
+    stopwatch: Stopwatch,
+    duration: Duration,
+    mode: TimerMode,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl Timer {
+    pub fn new(duration: Duration, mode: TimerMode) -> Self {
+        Self {
+            duration,
+            mode,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_seconds(seconds: f32, mode: TimerMode) -> Self {
+        Self::new(Duration::from_secs_f32(seconds), mode)
+    }
+
+    /// Add `delta`, usually [`Time::delta`], unless paused
+    ///
+    /// A repeating timer wraps around, possibly finishing several times in one tick.
+    pub fn tick(&mut self, delta: Duration) -> &Self {
+        self.times_finished_this_tick = 0;
+        if self.stopwatch.is_paused() {
+            return self;
+        }
+        if self.mode == TimerMode::Once && self.finished {
+            return self;
+        }
+        self.stopwatch.tick(delta);
+        self.finished = self.stopwatch.elapsed() >= self.duration;
+        if self.finished {
+            match self.mode {
+                TimerMode::Once => {
+                    self.times_finished_this_tick = 1;
+                    self.stopwatch.set_elapsed(self.duration);
+                }
+                TimerMode::Repeating if self.duration.is_zero() => {
+                    self.times_finished_this_tick = 1;
+                    self.stopwatch.reset();
+                }
+                TimerMode::Repeating => {
+                    let elapsed = self.stopwatch.elapsed().as_nanos();
+                    let duration = self.duration.as_nanos();
+                    self.times_finished_this_tick = (elapsed / duration) as u32;
+                    self.stopwatch
+                        .set_elapsed(Duration::from_nanos((elapsed % duration) as u64));
+                }
+            }
+        }
+        self
+    }
+
+    /// Whether the timer reached its duration; repeating timers only on the ticks that did
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Whether the last tick made the timer finish
+    pub fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    /// How many times the last tick made the timer finish, more than once for repeating
+    /// timers shorter than the tick
+    pub fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.stopwatch.elapsed()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed())
+    }
+
+    /// Fraction of the duration elapsed, in `[0, 1]`
+    pub fn fraction(&self) -> f32 {
+        if self.duration.is_zero() {
+            1.0
+        } else {
+            self.elapsed().as_secs_f32() / self.duration.as_secs_f32()
+        }
+    }
+
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    pub fn pause(&mut self) {
+        self.stopwatch.pause();
+    }
+
+    pub fn unpause(&mut self) {
+        self.stopwatch.unpause();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.stopwatch.is_paused()
+    }
+
+    pub fn reset(&mut self) {
+        self.stopwatch.reset();
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+}
+
+/// Updates the [`Time`] resource at the start of every update
+///
+/// [`FixedUpdate`] then follows the virtual clock, so it stops while time is paused.
+#[derive(Default)]
+pub struct TimePlugin;
+
+impl Plugin for TimePlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(mut fixed_time) = app.world.get_resource_mut::<FixedTime>() {
+            fixed_time.measure_real_time = false;
+        }
+        app.init_resource::<Time>()
+            .add_systems(First, (time_system, fixed_time_system).chain());
+    }
+}
+
+/// How [`time_system`] advances [`Time`], from the real clock by default
+///
+/// Insert it as a resource to drive time by hand, like in tests that need the same deltas
+/// on every run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeUpdateStrategy {
+    #[default]
+    Automatic,
+    /// Advance to this instant on every update
+    ManualInstant(Instant),
+    /// Advance by this duration on every update but the first, which has no delta
+    ManualDuration(Duration),
+}
+
+/// Advances [`Time`] as set by the [`TimeUpdateStrategy`] resource, to now without it
+pub fn time_system(mut time: ResMut<Time>, strategy: Option<Res<TimeUpdateStrategy>>) {
+    match strategy.as_deref().copied().unwrap_or_default() {
+        TimeUpdateStrategy::Automatic => time.update(),
+        TimeUpdateStrategy::ManualInstant(instant) => time.update_with_instant(instant),
+        TimeUpdateStrategy::ManualDuration(duration) => {
+            let last_update = time.last_update().unwrap_or(time.startup());
+            time.update_with_instant(last_update + duration);
+        }
+    }
+}
+
+/// Feeds the virtual delta of [`Time`] to [`FixedTime`]
+fn fixed_time_system(time: Res<Time>, fixed_time: Option<ResMut<FixedTime>>) {
+    if let Some(mut fixed_time) = fixed_time {
+        fixed_time.tick(time.delta());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::*;
+    use komorebi_app::prelude::*;
+    use komorebi_ecs::prelude::*;
+
+    #[test]
+    fn virtual_clock() {
+        let start = Instant::now();
+        let mut time = Time::new(start);
+        time.update_with_instant(start);
+        assert_eq!(time.delta(), Duration::ZERO);
+
+        time.update_with_instant(start + Duration::from_millis(10));
+        assert_eq!(time.delta(), Duration::from_millis(10));
+
+        time.set_relative_speed(2.0);
+        time.update_with_instant(start + Duration::from_millis(20));
+        assert_eq!(time.delta(), Duration::from_millis(20));
+
+        time.pause();
+        time.update_with_instant(start + Duration::from_millis(30));
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.raw_delta(), Duration::from_millis(10));
+
+        time.unpause();
+        time.update_with_instant(start + Duration::from_secs(10));
+        assert_eq!(time.delta(), Time::DEFAULT_MAX_DELTA);
+
+        assert_eq!(time.elapsed(), Duration::from_millis(280));
+        assert_eq!(time.raw_elapsed(), Duration::from_secs(10));
+        assert_eq!(time.frame_count(), 5);
+    }
+
+    #[test]
+    fn stopwatch_and_timers() {
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.tick(Duration::from_millis(5));
+        stopwatch.pause();
+        stopwatch.tick(Duration::from_millis(5));
+        assert_eq!(stopwatch.elapsed(), Duration::from_millis(5));
+
+        let mut once = Timer::new(Duration::from_millis(10), TimerMode::Once);
+        assert!(!once.tick(Duration::from_millis(6)).just_finished());
+        assert!(once.tick(Duration::from_millis(6)).just_finished());
+        assert!(!once.tick(Duration::from_millis(6)).just_finished());
+        assert!(once.finished());
+        assert_eq!(once.elapsed(), Duration::from_millis(10));
+
+        let mut repeating = Timer::new(Duration::from_millis(10), TimerMode::Repeating);
+        repeating.tick(Duration::from_millis(25));
+        assert_eq!(repeating.times_finished_this_tick(), 2);
+        assert_eq!(repeating.elapsed(), Duration::from_millis(5));
+        assert!(!repeating.tick(Duration::from_millis(1)).finished());
+    }
+
+    #[test]
+    fn manual_time() {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )))
+            .step_n(11);
+        let time = app.world.resource::<Time>();
+        assert_eq!(time.delta(), Duration::from_millis(100));
+        assert_eq!(time.elapsed(), Duration::from_secs(1));
+        assert_eq!(time.frame_count(), 11);
+    }
+
+    #[test]
+    fn fixed_update_follows_virtual_time() {
+        #[derive(Default)]
+        struct Steps(u32);
+
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .init_resource::<Steps>()
+            .add_systems(FixedUpdate, |mut steps: ResMut<Steps>| steps.0 += 1);
+        app.world.resource_mut::<Time>().pause();
+        app.update();
+        std::thread::sleep(Duration::from_millis(40));
+        app.update();
+        assert_eq!(app.world.resource::<Steps>().0, 0);
+
+        app.world.resource_mut::<Time>().unpause();
+        std::thread::sleep(Duration::from_millis(40));
+        app.update();
+        assert!(app.world.resource::<Steps>().0 >= 2);
+    }
+}
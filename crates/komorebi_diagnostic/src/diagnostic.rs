@@ -0,0 +1,244 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    time::Instant,
+};
+
+/// A value measured at some point in time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiagnosticMeasurement {
+    pub time: Instant,
+    pub value: f64,
+}
+
+/// A named measurement, keeping its most recent values in a ring buffer
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    name: Cow<'static, str>,
+    /// Unit appended to the values when printed, like `ms`
+    pub suffix: Cow<'static, str>,
+    history: VecDeque<DiagnosticMeasurement>,
+    max_history_length: usize,
+    /// Sum of the values in `history`
+    sum: f64,
+    smoothing_factor: f64,
+    smoothed: Option<f64>,
+}
+
+impl Diagnostic {
+    pub const DEFAULT_MAX_HISTORY_LENGTH: usize = 120;
+    /// An exponential moving average over about 20 measurements
+    pub const DEFAULT_SMOOTHING_FACTOR: f64 = 2.0 / 21.0;
+
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            suffix: Cow::Borrowed(""),
+            history: VecDeque::new(),
+            max_history_length: Self::DEFAULT_MAX_HISTORY_LENGTH,
+            sum: 0.0,
+            smoothing_factor: Self::DEFAULT_SMOOTHING_FACTOR,
+            smoothed: None,
+        }
+    }
+
+    pub fn with_suffix(mut self, suffix: impl Into<Cow<'static, str>>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    /// Number of measurements kept, dropping the oldest ones first
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_history_length` is zero.
+    pub fn with_max_history_length(mut self, max_history_length: usize) -> Self {
+        assert!(max_history_length > 0, "diagnostic history cannot be empty");
+        self.max_history_length = max_history_length;
+        while self.history.len() > max_history_length {
+            self.pop_front();
+        }
+        self
+    }
+
+    /// Weight of each new measurement in [`Diagnostic::smoothed`], in `(0, 1]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `smoothing_factor` is out of range.
+    pub fn with_smoothing_factor(mut self, smoothing_factor: f64) -> Self {
+        assert!(
+            smoothing_factor > 0.0 && smoothing_factor <= 1.0,
+            "smoothing factor {smoothing_factor} is not in (0, 1]"
+        );
+        self.smoothing_factor = smoothing_factor;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn add_measurement(&mut self, value: f64) {
+        self.add_measurement_at(Instant::now(), value);
+    }
+
+    pub fn add_measurement_at(&mut self, time: Instant, value: f64) {
+        if self.history.len() == self.max_history_length {
+            self.pop_front();
+        }
+        self.history
+            .push_back(DiagnosticMeasurement { time, value });
+        self.sum += value;
+        self.smoothed = Some(match self.smoothed {
+            Some(smoothed) => smoothed + self.smoothing_factor * (value - smoothed),
+            None => value,
+        });
+    }
+
+    fn pop_front(&mut self) {
+        if let Some(measurement) = self.history.pop_front() {
+            self.sum -= measurement.value;
+        }
+    }
+
+    /// The latest value
+    pub fn value(&self) -> Option<f64> {
+        self.history.back().map(|m| m.value)
+    }
+
+    /// Mean of the values in the history
+    pub fn average(&self) -> Option<f64> {
+        (!self.history.is_empty()).then(|| self.sum / self.history.len() as f64)
+    }
+
+    /// Exponential moving average of every value measured, see
+    /// [`Diagnostic::with_smoothing_factor`]
+    pub fn smoothed(&self) -> Option<f64> {
+        self.smoothed
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn max_history_length(&self) -> usize {
+        self.max_history_length
+    }
+
+    /// Measurements in the history, oldest first
+    pub fn measurements(&self) -> impl DoubleEndedIterator<Item = &DiagnosticMeasurement> {
+        self.history.iter()
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = f64> + '_ {
+        self.history.iter().map(|m| m.value)
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.sum = 0.0;
+        self.smoothed = None;
+    }
+}
+
+/// Resource holding every [`Diagnostic`], by name
+pub struct DiagnosticsStore {
+    diagnostics: BTreeMap<Cow<'static, str>, Diagnostic>,
+    /// History length of the diagnostics created by [`DiagnosticsStore::add_measurement`]
+    pub max_history_length: usize,
+    /// Smoothing factor of the diagnostics created by [`DiagnosticsStore::add_measurement`]
+    pub smoothing_factor: f64,
+}
+
+impl Default for DiagnosticsStore {
+    fn default() -> Self {
+        Self {
+            diagnostics: BTreeMap::new(),
+            max_history_length: Diagnostic::DEFAULT_MAX_HISTORY_LENGTH,
+            smoothing_factor: Diagnostic::DEFAULT_SMOOTHING_FACTOR,
+        }
+    }
+}
+
+impl DiagnosticsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a diagnostic, replacing the one of the same name
+    pub fn add(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.insert(diagnostic.name.clone(), diagnostic);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Diagnostic> {
+        self.diagnostics.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Diagnostic> {
+        self.diagnostics.get_mut(name)
+    }
+
+    /// Add a value to the diagnostic called `name`, creating it if needed
+    pub fn add_measurement(&mut self, name: impl Into<Cow<'static, str>>, value: f64) {
+        let name = name.into();
+        if let Some(diagnostic) = self.diagnostics.get_mut(&name) {
+            diagnostic.add_measurement(value);
+            return;
+        }
+        let mut diagnostic = Diagnostic::new(name)
+            .with_max_history_length(self.max_history_length)
+            .with_smoothing_factor(self.smoothing_factor);
+        diagnostic.add_measurement(value);
+        self.add(diagnostic);
+    }
+
+    /// Iterate over the diagnostics, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn history() {
+        let mut diagnostic = Diagnostic::new("test")
+            .with_max_history_length(3)
+            .with_smoothing_factor(0.5);
+        assert_eq!(diagnostic.value(), None);
+        assert_eq!(diagnostic.average(), None);
+
+        for value in [1.0, 2.0, 3.0, 4.0] {
+            diagnostic.add_measurement(value);
+        }
+        assert_eq!(diagnostic.history_len(), 3);
+        assert_eq!(diagnostic.values().collect::<Vec<_>>(), [2.0, 3.0, 4.0]);
+        assert_eq!(diagnostic.value(), Some(4.0));
+        assert_eq!(diagnostic.average(), Some(3.0));
+        // 1, then 1.5, 2.25 and 3.125
+        assert_eq!(diagnostic.smoothed(), Some(3.125));
+
+        diagnostic.clear_history();
+        assert_eq!(diagnostic.smoothed(), None);
+    }
+
+    #[test]
+    fn store() {
+        let mut store = DiagnosticsStore::new();
+        store.max_history_length = 2;
+        store.add(Diagnostic::new("b").with_suffix("ms"));
+        store.add_measurement("b", 1.0);
+        store.add_measurement("a", 1.0);
+        store.add_measurement("a", 2.0);
+        store.add_measurement("a", 3.0);
+
+        let names: Vec<_> = store.iter().map(|d| d.name()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(store.get("a").unwrap().history_len(), 2);
+        assert_eq!(store.get("b").unwrap().suffix, "ms");
+        assert!(store.get("c").is_none());
+    }
+}
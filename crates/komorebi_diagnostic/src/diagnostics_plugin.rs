@@ -0,0 +1,162 @@
+use komorebi_app::{prelude::*, PluginDependency};
+use komorebi_core::{Time, TimePlugin};
+use komorebi_ecs::{SystemTimings, World};
+
+use crate::{Diagnostic, DiagnosticsStore};
+
+/// Measures the frame time, frame rate, entity and component counts, and system timings into
+/// the [`DiagnosticsStore`] resource at the end of every update
+///
+/// Component counts are named `storage/{storage type}`, and system timings
+/// `system/{system name}`, followed by ` #{index}` for systems of a schedule that share a name.
+#[derive(Clone, Debug)]
+pub struct DiagnosticsPlugin {
+    pub max_history_length: usize,
+    /// See [`Diagnostic::with_smoothing_factor`]
+    pub smoothing_factor: f64,
+    /// Measure the component count of every storage
+    pub storage_counts: bool,
+    /// Measure how long every system runs for, which adds a little overhead to each
+    pub system_timings: bool,
+}
+
+impl Default for DiagnosticsPlugin {
+    fn default() -> Self {
+        Self {
+            max_history_length: Diagnostic::DEFAULT_MAX_HISTORY_LENGTH,
+            smoothing_factor: Diagnostic::DEFAULT_SMOOTHING_FACTOR,
+            storage_counts: true,
+            system_timings: true,
+        }
+    }
+}
+
+impl DiagnosticsPlugin {
+    /// Duration of the last update, in milliseconds
+    pub const FRAME_TIME: &'static str = "frame_time";
+    /// Updates per second
+    pub const FPS: &'static str = "fps";
+    pub const ENTITY_COUNT: &'static str = "entity_count";
+    pub const STORAGE_PREFIX: &'static str = "storage/";
+    /// Prefix of the system timings, in milliseconds
+    pub const SYSTEM_PREFIX: &'static str = "system/";
+}
+
+/// Settings of [`diagnostics_system`]
+struct DiagnosticsSettings {
+    storage_counts: bool,
+}
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut store = DiagnosticsStore::new();
+        store.max_history_length = self.max_history_length;
+        store.smoothing_factor = self.smoothing_factor;
+        let diagnostic = |name| {
+            Diagnostic::new(name)
+                .with_max_history_length(self.max_history_length)
+                .with_smoothing_factor(self.smoothing_factor)
+        };
+        store.add(diagnostic(Self::FRAME_TIME).with_suffix("ms"));
+        store.add(diagnostic(Self::FPS));
+        store.add(diagnostic(Self::ENTITY_COUNT));
+
+        app.insert_resource(store)
+            .insert_resource(DiagnosticsSettings {
+                storage_counts: self.storage_counts,
+            })
+            .add_systems(Last, diagnostics_system);
+        if self.system_timings {
+            app.init_resource::<SystemTimings>();
+        }
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::of::<TimePlugin>()]
+    }
+}
+
+/// Adds the measurements of this update to the [`DiagnosticsStore`]
+pub fn diagnostics_system(world: &mut World) {
+    let delta = world.resource::<Time>().raw_delta();
+    let entity_count = world.entity_count();
+    let component_counts: Vec<_> = match world.resource::<DiagnosticsSettings>().storage_counts {
+        true => world.storage_counts().collect(),
+        false => Vec::new(),
+    };
+    let system_timings = world
+        .get_resource_mut::<SystemTimings>()
+        .map(|mut timings| timings.take())
+        .unwrap_or_default();
+
+    let mut store = world.resource_mut::<DiagnosticsStore>();
+    // The first update has no delta
+    if !delta.is_zero() {
+        store.add_measurement(DiagnosticsPlugin::FRAME_TIME, delta.as_secs_f64() * 1000.0);
+        store.add_measurement(DiagnosticsPlugin::FPS, 1.0 / delta.as_secs_f64());
+    }
+    store.add_measurement(DiagnosticsPlugin::ENTITY_COUNT, entity_count as f64);
+    for (name, count) in component_counts {
+        store.add_measurement(
+            format!("{}{name}", DiagnosticsPlugin::STORAGE_PREFIX),
+            count as f64,
+        );
+    }
+    for (name, duration) in system_timings {
+        let name = format!("{}{name}", DiagnosticsPlugin::SYSTEM_PREFIX);
+        if store.get(&name).is_none() {
+            let diagnostic = Diagnostic::new(name.clone())
+                .with_suffix("ms")
+                .with_max_history_length(store.max_history_length)
+                .with_smoothing_factor(store.smoothing_factor);
+            store.add(diagnostic);
+        }
+        store.add_measurement(name, duration.as_secs_f64() * 1000.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use komorebi_app::prelude::*;
+    use komorebi_core::prelude::*;
+    use komorebi_ecs::prelude::*;
+
+    fn spawn_enemy(world: &mut World) {
+        let entity = world.spawn();
+        world.insert::<VecStorage<u32>>(entity, 1);
+    }
+
+    #[test]
+    fn measurements() {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .add_plugins(DiagnosticsPlugin::default())
+            .add_systems(Update, spawn_enemy);
+        app.world.register::<VecStorage<u32>>();
+        app.update();
+        app.update();
+        app.update();
+
+        let store = app.world.resource::<DiagnosticsStore>();
+        let value = |name: &str| store.get(name).unwrap().value().unwrap();
+        assert_eq!(
+            store
+                .get(DiagnosticsPlugin::FRAME_TIME)
+                .unwrap()
+                .history_len(),
+            2
+        );
+        assert!(value(DiagnosticsPlugin::FPS) > 0.0);
+        assert_eq!(value(DiagnosticsPlugin::ENTITY_COUNT), 3.0);
+        let storage = format!("storage/{}", std::any::type_name::<VecStorage<u32>>());
+        assert_eq!(value(&storage), 3.0);
+        let system = store
+            .iter()
+            .find(|d| d.name().ends_with("::spawn_enemy"))
+            .unwrap();
+        assert!(system.name().starts_with(DiagnosticsPlugin::SYSTEM_PREFIX));
+        assert_eq!(system.suffix, "ms");
+        assert_eq!(system.history_len(), 3);
+    }
+}
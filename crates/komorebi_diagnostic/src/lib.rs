@@ -0,0 +1,12 @@
+mod diagnostic;
+mod diagnostics_plugin;
+mod log_diagnostics_plugin;
+
+pub use diagnostic::*;
+pub use diagnostics_plugin::*;
+pub use log_diagnostics_plugin::*;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{Diagnostic, DiagnosticsPlugin, DiagnosticsStore, LogDiagnosticsPlugin};
+}
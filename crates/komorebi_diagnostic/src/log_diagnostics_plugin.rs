@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use komorebi_app::{prelude::*, PluginDependency};
+use komorebi_core::{Time, Timer, TimerMode};
+use komorebi_ecs::{IntoSystemConfigs, Res, ResMut};
+use komorebi_utils::tracing::info;
+
+use crate::{diagnostics_system, Diagnostic, DiagnosticsPlugin, DiagnosticsStore};
+
+/// Logs the diagnostics of the [`DiagnosticsStore`] through `tracing` every `wait_duration`
+#[derive(Clone, Debug)]
+pub struct LogDiagnosticsPlugin {
+    pub wait_duration: Duration,
+    /// Names of the diagnostics to log, every diagnostic by default
+    pub filter: Option<Vec<String>>,
+}
+
+impl Default for LogDiagnosticsPlugin {
+    fn default() -> Self {
+        Self {
+            wait_duration: Duration::from_secs(1),
+            filter: None,
+        }
+    }
+}
+
+impl LogDiagnosticsPlugin {
+    /// Only log the diagnostics called `filter`
+    pub fn filtered(filter: Vec<String>) -> Self {
+        Self {
+            filter: Some(filter),
+            ..Default::default()
+        }
+    }
+}
+
+struct LogDiagnosticsState {
+    timer: Timer,
+    filter: Option<Vec<String>>,
+}
+
+impl Plugin for LogDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LogDiagnosticsState {
+            timer: Timer::new(self.wait_duration, TimerMode::Repeating),
+            filter: self.filter.clone(),
+        })
+        .add_systems(Last, log_diagnostics_system.after(diagnostics_system));
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::of::<DiagnosticsPlugin>()]
+    }
+}
+
+/// Logs the diagnostics once the wait duration elapsed, in real time
+fn log_diagnostics_system(
+    mut state: ResMut<LogDiagnosticsState>,
+    time: Res<Time>,
+    store: Res<DiagnosticsStore>,
+) {
+    if !state.timer.tick(time.raw_delta()).just_finished() {
+        return;
+    }
+    let diagnostics: Vec<_> = match &state.filter {
+        Some(filter) => filter.iter().filter_map(|name| store.get(name)).collect(),
+        None => store.iter().collect(),
+    };
+    let name_width = diagnostics
+        .iter()
+        .map(|d| d.name().len())
+        .max()
+        .unwrap_or(0);
+    for diagnostic in diagnostics {
+        log_diagnostic(diagnostic, name_width);
+    }
+}
+
+fn log_diagnostic(diagnostic: &Diagnostic, name_width: usize) {
+    let (Some(smoothed), Some(average)) = (diagnostic.smoothed(), diagnostic.average()) else {
+        return;
+    };
+    let name = diagnostic.name();
+    let suffix = &diagnostic.suffix;
+    info!("{name:<name_width$}: {smoothed:>11.4}{suffix:<2} (avg {average:.4}{suffix})");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::*;
+    use komorebi_app::prelude::*;
+    use komorebi_core::prelude::*;
+    use komorebi_utils::{tracing, tracing_subscriber};
+
+    /// Collects what the subscriber of the test writes
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn log_diagnostics() {
+        let output = Output::default();
+        let writer = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .add_plugins(DiagnosticsPlugin::default())
+            .add_plugins(LogDiagnosticsPlugin {
+                wait_duration: std::time::Duration::ZERO,
+                filter: Some(vec![DiagnosticsPlugin::ENTITY_COUNT.to_string()]),
+            });
+        tracing::subscriber::with_default(subscriber, || {
+            app.update();
+            app.update();
+        });
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2, "{output}");
+        assert!(lines
+            .iter()
+            .all(|line| line.ends_with("entity_count:      0.0000   (avg 0.0000)")));
+
+        assert!(App::new()
+            .try_add_plugins(LogDiagnosticsPlugin::default())
+            .is_err());
+    }
+}
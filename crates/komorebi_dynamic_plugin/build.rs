@@ -0,0 +1,10 @@
+//! Records the compiler building the engine, which plugins must share with the host
+
+fn main() {
+    let version = rustc_version::version_meta().expect("cannot query the rustc version");
+    let commit_hash = version.commit_hash.as_deref().unwrap_or("unknown");
+    println!(
+        "cargo:rustc-env=KOMOREBI_RUSTC_VERSION={} ({commit_hash})",
+        version.semver
+    );
+}
@@ -0,0 +1,30 @@
+mod loader;
+
+pub use loader::*;
+
+#[doc(hidden)]
+pub mod __macro_exports {
+    pub use komorebi_app::Plugin;
+}
+
+/// Exports a plugin from a `cdylib` crate so it can be loaded by a [`DynamicPluginLoader`]
+///
+/// Takes an expression creating the plugin:
+///
+/// ```ignore
+/// komorebi_dynamic_plugin::export_plugin!(MyPlugin::default());
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+    ($plugin:expr) => {
+        #[no_mangle]
+        pub static KOMOREBI_PLUGIN_STAMP: $crate::DynamicPluginStamp =
+            $crate::DynamicPluginStamp::CURRENT;
+
+        #[no_mangle]
+        pub extern "C" fn komorebi_create_plugin() -> *mut ::std::ffi::c_void {
+            let plugin: Box<dyn $crate::__macro_exports::Plugin> = Box::new($plugin);
+            Box::into_raw(Box::new(plugin)) as *mut ::std::ffi::c_void
+        }
+    };
+}
@@ -0,0 +1,297 @@
+use std::{
+    error::Error,
+    ffi::{c_void, OsStr},
+    fmt,
+};
+
+use komorebi_app::{App, AppError, Plugin};
+use komorebi_utils::tracing::info;
+use libloading::{Library, Symbol};
+
+/// Bumped whenever the way plugins are exported changes
+pub const DYNAMIC_PLUGIN_ABI_VERSION: u32 = 3;
+
+/// Name of the [`DynamicPluginStamp`] static exported by [`export_plugin!`](crate::export_plugin)
+pub const PLUGIN_STAMP_SYMBOL: &str = "KOMOREBI_PLUGIN_STAMP";
+/// Name of the constructor exported by [`export_plugin!`](crate::export_plugin)
+pub const CREATE_PLUGIN_SYMBOL: &str = "komorebi_create_plugin";
+
+/// Returns a `Box<Box<dyn Plugin>>` turned into a raw pointer, which the host takes back
+type CreatePlugin = unsafe extern "C" fn() -> *mut c_void;
+
+/// Versions a dynamic plugin was built against
+///
+/// A plugin is only loaded when its stamp equals [`DynamicPluginStamp::CURRENT`]. Plugins pass
+/// Rust trait objects to the host, whose layout is only stable for a given compiler, so the
+/// stamp also holds the version of rustc.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamicPluginStamp {
+    /// Checked first, as the layout of the other fields may differ between ABI versions
+    pub abi_version: u32,
+    /// Major, minor and patch version of the engine
+    pub engine_version: [u32; 3],
+    /// Version and commit hash of rustc, padded with zeros
+    pub compiler: [u8; 64],
+}
+
+impl DynamicPluginStamp {
+    /// The stamp of this build of the engine
+    pub const CURRENT: Self = Self {
+        abi_version: DYNAMIC_PLUGIN_ABI_VERSION,
+        engine_version: [
+            parse_version(env!("CARGO_PKG_VERSION_MAJOR")),
+            parse_version(env!("CARGO_PKG_VERSION_MINOR")),
+            parse_version(env!("CARGO_PKG_VERSION_PATCH")),
+        ],
+        // Set by the build script
+        compiler: pad_compiler(env!("KOMOREBI_RUSTC_VERSION")),
+    };
+
+    /// Fails if a plugin with this stamp cannot be loaded by this build of the engine
+    pub fn check(&self) -> Result<(), DynamicPluginLoadError> {
+        if self.abi_version != DYNAMIC_PLUGIN_ABI_VERSION {
+            return Err(DynamicPluginLoadError::AbiMismatch {
+                found: self.abi_version,
+            });
+        }
+        if self.engine_version != Self::CURRENT.engine_version {
+            return Err(DynamicPluginLoadError::EngineVersionMismatch {
+                found: format_version(self.engine_version),
+            });
+        }
+        if self.compiler != Self::CURRENT.compiler {
+            return Err(DynamicPluginLoadError::CompilerMismatch {
+                found: format_compiler(&self.compiler),
+            });
+        }
+        Ok(())
+    }
+}
+
+const fn parse_version(part: &str) -> u32 {
+    let bytes = part.as_bytes();
+    let mut version = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        version = version * 10 + (bytes[i] - b'0') as u32;
+        i += 1;
+    }
+    version
+}
+
+fn format_version([major, minor, patch]: [u32; 3]) -> String {
+    format!("{major}.{minor}.{patch}")
+}
+
+/// Truncates longer versions, which only happens for unusual custom builds of rustc
+const fn pad_compiler(version: &str) -> [u8; 64] {
+    let bytes = version.as_bytes();
+    let mut compiler = [0; 64];
+    let mut i = 0;
+    while i < bytes.len() && i < compiler.len() {
+        compiler[i] = bytes[i];
+        i += 1;
+    }
+    compiler
+}
+
+fn format_compiler(compiler: &[u8; 64]) -> String {
+    let len = compiler
+        .iter()
+        .position(|&b| b == 0)
+        .unwrap_or(compiler.len());
+    String::from_utf8_lossy(&compiler[..len]).into_owned()
+}
+
+/// Error returned when a dynamic plugin cannot be loaded
+#[derive(Debug)]
+pub enum DynamicPluginLoadError {
+    /// The shared library could not be opened
+    Library(libloading::Error),
+    /// The library does not export a symbol of [`export_plugin!`](crate::export_plugin)
+    MissingSymbol {
+        symbol: &'static str,
+        source: libloading::Error,
+    },
+    /// The plugin was exported with another [`DYNAMIC_PLUGIN_ABI_VERSION`]
+    AbiMismatch { found: u32 },
+    /// The plugin was built against another version of the engine
+    EngineVersionMismatch { found: String },
+    /// The plugin was built with another version of rustc
+    CompilerMismatch { found: String },
+    /// The plugin was loaded but could not be added to the app
+    App(AppError),
+}
+
+impl fmt::Display for DynamicPluginLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicPluginLoadError::Library(e) => write!(f, "cannot open plugin library: {e}"),
+            DynamicPluginLoadError::MissingSymbol { symbol, .. } => {
+                write!(f, "plugin library does not export {symbol}")
+            }
+            DynamicPluginLoadError::AbiMismatch { found } => write!(
+                f,
+                "plugin ABI version {found} does not match {DYNAMIC_PLUGIN_ABI_VERSION}"
+            ),
+            DynamicPluginLoadError::EngineVersionMismatch { found } => write!(
+                f,
+                "plugin was built for engine version {found}, not {}",
+                format_version(DynamicPluginStamp::CURRENT.engine_version)
+            ),
+            DynamicPluginLoadError::CompilerMismatch { found } => write!(
+                f,
+                "plugin was built with rustc {found}, not {}",
+                format_compiler(&DynamicPluginStamp::CURRENT.compiler)
+            ),
+            DynamicPluginLoadError::App(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for DynamicPluginLoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DynamicPluginLoadError::Library(e)
+            | DynamicPluginLoadError::MissingSymbol { source: e, .. } => Some(e),
+            DynamicPluginLoadError::App(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A plugin created by a shared library, with the library it came from
+///
+/// The plugin's code lives in the library, so the plugin is dropped before the library is
+/// unloaded. Anything the plugin added to an [`App`] runs that code too: drop the app first,
+/// or [`leak`](Self::leak) the library.
+pub struct DynamicPlugin {
+    // Declared before `library`, so that it is dropped first
+    plugin: Box<dyn Plugin>,
+    library: Library,
+}
+
+impl DynamicPlugin {
+    pub fn plugin(&self) -> &dyn Plugin {
+        self.plugin.as_ref()
+    }
+
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// Keeps the library loaded until the process exits, so the plugin can be used anywhere
+    pub fn leak(self) -> Box<dyn Plugin> {
+        let DynamicPlugin { plugin, library } = self;
+        std::mem::forget(library);
+        plugin
+    }
+}
+
+/// Opens the shared library at `path`, checks its [`DynamicPluginStamp`] and creates its plugin
+///
+/// # Safety
+///
+/// Running code from a library is unsafe: the library must have been built by
+/// [`export_plugin!`](crate::export_plugin) with the same compiler as the host, and its
+/// initialization routines run on load.
+pub unsafe fn dynamically_load_plugin(
+    path: impl AsRef<OsStr>,
+) -> Result<DynamicPlugin, DynamicPluginLoadError> {
+    let library = Library::new(path).map_err(DynamicPluginLoadError::Library)?;
+    let stamp: Symbol<*const DynamicPluginStamp> = library
+        .get(PLUGIN_STAMP_SYMBOL.as_bytes())
+        .map_err(|source| DynamicPluginLoadError::MissingSymbol {
+            symbol: PLUGIN_STAMP_SYMBOL,
+            source,
+        })?;
+    // Read the ABI version alone first, it is the only field every version agrees on
+    let abi_version = std::ptr::read(*stamp as *const u32);
+    if abi_version != DYNAMIC_PLUGIN_ABI_VERSION {
+        return Err(DynamicPluginLoadError::AbiMismatch { found: abi_version });
+    }
+    (**stamp).check()?;
+
+    let create_plugin: Symbol<CreatePlugin> = library
+        .get(CREATE_PLUGIN_SYMBOL.as_bytes())
+        .map_err(|source| DynamicPluginLoadError::MissingSymbol {
+            symbol: CREATE_PLUGIN_SYMBOL,
+            source,
+        })?;
+    let plugin = *Box::from_raw(create_plugin() as *mut Box<dyn Plugin>);
+    Ok(DynamicPlugin { plugin, library })
+}
+
+/// Adds plugins loaded from shared libraries to an [`App`]
+pub trait DynamicPluginLoader {
+    /// Loads the plugin exported by the library at `path`, see [`dynamically_load_plugin`]
+    ///
+    /// The library stays loaded until the process exits.
+    ///
+    /// # Safety
+    ///
+    /// See [`dynamically_load_plugin`].
+    unsafe fn load_plugin(
+        &mut self,
+        path: impl AsRef<OsStr>,
+    ) -> Result<&mut Self, DynamicPluginLoadError>;
+}
+
+impl DynamicPluginLoader for App {
+    unsafe fn load_plugin(
+        &mut self,
+        path: impl AsRef<OsStr>,
+    ) -> Result<&mut Self, DynamicPluginLoadError> {
+        let path = path.as_ref();
+        let plugin = dynamically_load_plugin(path)?;
+        info!("loaded plugin {} from {path:?}", plugin.plugin().name());
+        // The systems and resources the plugin adds may outlive the app, so the library is
+        // never unloaded
+        self.try_add_plugins(plugin.leak())
+            .map_err(DynamicPluginLoadError::App)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pad_compiler;
+    use crate::*;
+    use komorebi_app::prelude::*;
+
+    #[test]
+    fn stamp_check() {
+        assert!(DynamicPluginStamp::CURRENT.check().is_ok());
+        let stamp = DynamicPluginStamp {
+            abi_version: DYNAMIC_PLUGIN_ABI_VERSION + 1,
+            ..DynamicPluginStamp::CURRENT
+        };
+        assert!(matches!(
+            stamp.check(),
+            Err(DynamicPluginLoadError::AbiMismatch { .. })
+        ));
+        let stamp = DynamicPluginStamp {
+            engine_version: [0, 0, 0],
+            ..DynamicPluginStamp::CURRENT
+        };
+        assert!(matches!(
+            stamp.check(),
+            Err(DynamicPluginLoadError::EngineVersionMismatch { .. })
+        ));
+        let stamp = DynamicPluginStamp {
+            compiler: pad_compiler("1.0.0 (unknown)"),
+            ..DynamicPluginStamp::CURRENT
+        };
+        let error = stamp.check().unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("plugin was built with rustc 1.0.0 (unknown), not "));
+    }
+
+    #[test]
+    fn missing_library() {
+        let mut app = App::new();
+        let result = unsafe { app.load_plugin("does-not-exist.so") };
+        assert!(matches!(result, Err(DynamicPluginLoadError::Library(_))));
+    }
+}
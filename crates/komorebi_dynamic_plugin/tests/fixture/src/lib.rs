@@ -0,0 +1,14 @@
+use komorebi_app::{App, Plugin};
+
+/// Inserts [`MESSAGE`] as a `String` resource, which the host can read without sharing types
+pub struct FixturePlugin;
+
+pub const MESSAGE: &str = "loaded from a shared library";
+
+impl Plugin for FixturePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MESSAGE.to_string());
+    }
+}
+
+komorebi_dynamic_plugin::export_plugin!(FixturePlugin);
@@ -0,0 +1,38 @@
+//! Loads the plugin of this crate, whose `cdylib` cargo builds before running these tests
+
+use std::path::PathBuf;
+
+use komorebi_app::prelude::*;
+use komorebi_dynamic_plugin::*;
+
+/// The `cdylib` of this crate, built next to the test binary in `<target>/<profile>/deps`
+fn fixture_path() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.with_file_name(libloading::library_filename(env!("CARGO_PKG_NAME")))
+}
+
+#[test]
+fn load_and_drop() {
+    let plugin = unsafe { dynamically_load_plugin(fixture_path()) }.unwrap();
+    assert!(plugin.plugin().name().ends_with("FixturePlugin"));
+    {
+        let mut app = App::new();
+        plugin.plugin().build(&mut app);
+        assert_eq!(
+            *app.world.resource::<String>(),
+            "loaded from a shared library"
+        );
+    }
+    // The app went first, so the library can be unloaded
+    drop(plugin);
+}
+
+#[test]
+fn load_into_app() {
+    let mut app = App::new();
+    unsafe { app.load_plugin(fixture_path()) }.unwrap();
+    assert_eq!(
+        *app.world.resource::<String>(),
+        "loaded from a shared library"
+    );
+}
@@ -0,0 +1,163 @@
+use std::any::{type_name, TypeId};
+
+use fxhash::FxHashMap;
+
+use crate::{Resource, Storage};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+enum AccessKind {
+    Resource,
+    Storage,
+}
+
+/// The resources and storages a system reads and writes
+///
+/// Two systems with conflicting access cannot run at the same time, and the order they run
+/// in matters; see [`Schedule::ambiguities`](crate::Schedule::ambiguities).
+#[derive(Clone, Debug, Default)]
+pub struct Access {
+    reads: FxHashMap<(AccessKind, TypeId), &'static str>,
+    writes: FxHashMap<(AccessKind, TypeId), &'static str>,
+    writes_world: bool,
+}
+
+impl Access {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Access to the whole world, as needed by exclusive systems
+    pub fn world() -> Self {
+        Self {
+            writes_world: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn add_read_resource<R: Resource>(&mut self) {
+        self.add_read(AccessKind::Resource, TypeId::of::<R>(), type_name::<R>());
+    }
+
+    pub fn add_write_resource<R: Resource>(&mut self) {
+        self.add_write(AccessKind::Resource, TypeId::of::<R>(), type_name::<R>());
+    }
+
+    pub fn add_read_storage<S: Storage>(&mut self) {
+        self.add_read(AccessKind::Storage, TypeId::of::<S>(), type_name::<S>());
+    }
+
+    pub fn add_write_storage<S: Storage>(&mut self) {
+        self.add_write(AccessKind::Storage, TypeId::of::<S>(), type_name::<S>());
+    }
+
+    /// Resources and storages are locked while borrowed, so even two reads of the same one by
+    /// a system would conflict with each other
+    fn add_read(&mut self, kind: AccessKind, id: TypeId, name: &'static str) {
+        if self.writes.contains_key(&(kind, id)) {
+            panic!("{name} is both read and written by the same system");
+        }
+        if self.reads.insert((kind, id), name).is_some() {
+            panic!("{name} is read more than once by the same system");
+        }
+    }
+
+    fn add_write(&mut self, kind: AccessKind, id: TypeId, name: &'static str) {
+        if self.reads.contains_key(&(kind, id)) || self.writes.contains_key(&(kind, id)) {
+            panic!("{name} is accessed more than once by the same system, and written");
+        }
+        self.writes.insert((kind, id), name);
+    }
+
+    /// Whether this is access to the whole world
+    pub fn writes_world(&self) -> bool {
+        self.writes_world
+    }
+
+    /// Whether nothing is accessed
+    pub fn is_empty(&self) -> bool {
+        !self.writes_world && self.reads.is_empty() && self.writes.is_empty()
+    }
+
+    /// Add the access of `other`, as when two systems are combined into one
+    pub fn extend(&mut self, other: &Access) {
+        self.writes_world |= other.writes_world;
+        self.reads.extend(other.reads.iter());
+        self.writes.extend(other.writes.iter());
+        self.reads.retain(|key, _| !self.writes.contains_key(key));
+    }
+
+    /// Names of the resources and storages accessed by both, with at least one writer, sorted
+    ///
+    /// Access to the whole world conflicts with any access, and is reported as `World`.
+    pub fn conflicts(&self, other: &Access) -> Vec<&'static str> {
+        if (self.writes_world && !other.is_empty()) || (other.writes_world && !self.is_empty()) {
+            return vec!["World"];
+        }
+        let mut conflicts: Vec<&'static str> = self
+            .writes
+            .iter()
+            .filter(|(key, _)| other.reads.contains_key(key) || other.writes.contains_key(key))
+            .chain(
+                self.reads
+                    .iter()
+                    .filter(|(key, _)| other.writes.contains_key(key)),
+            )
+            .map(|(_, name)| *name)
+            .collect();
+        conflicts.sort_unstable();
+        conflicts
+    }
+
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        self.conflicts(other).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VecStorage;
+
+    #[test]
+    fn conflicts() {
+        let mut a = Access::new();
+        a.add_read_resource::<u32>();
+        a.add_write_storage::<VecStorage<u8>>();
+        let mut b = Access::new();
+        b.add_read_resource::<u32>();
+        assert!(a.is_compatible(&b));
+
+        b.add_read_storage::<VecStorage<u8>>();
+        assert_eq!(
+            a.conflicts(&b),
+            [type_name::<VecStorage<u8>>()],
+            "read against write conflicts"
+        );
+
+        let mut resource = Access::new();
+        resource.add_write_resource::<VecStorage<u8>>();
+        assert!(
+            a.is_compatible(&resource),
+            "resources and storages are distinct"
+        );
+
+        assert_eq!(Access::world().conflicts(&b), ["World"]);
+        assert!(Access::world().is_compatible(&Access::new()));
+    }
+
+    #[test]
+    #[should_panic(expected = "same system")]
+    fn self_conflict() {
+        let mut access = Access::new();
+        access.add_read_resource::<u32>();
+        access.add_write_resource::<u32>();
+    }
+
+    #[test]
+    #[should_panic(expected = "read more than once")]
+    fn duplicate_read() {
+        let mut access = Access::new();
+        access.add_read_storage::<VecStorage<u8>>();
+        access.add_read_storage::<VecStorage<u8>>();
+    }
+}
@@ -0,0 +1,265 @@
+use std::marker::PhantomData;
+
+use crate::{Res, ResMut, SystemMeta, SystemParam, World};
+
+/// A message passed between systems through an [`Events`] resource
+pub trait Event: Send + 'static {}
+
+impl<T: Send + 'static> Event for T {}
+
+/// Resource queueing events of type `E`
+///
+/// Events are kept for two [`update`](Events::update)s, normally one per frame, so that
+/// every [`EventReader`] sees each event once whether it runs before or after the writer.
+pub struct Events<E> {
+    /// Events sent before the last update, with their ids
+    previous: Vec<(usize, E)>,
+    /// Events sent since the last update, with their ids
+    current: Vec<(usize, E)>,
+    event_count: usize,
+}
+
+impl<E> Default for Events<E> {
+    fn default() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            event_count: 0,
+        }
+    }
+}
+
+impl<E: Event> Events<E> {
+    pub fn send(&mut self, event: E) {
+        self.current.push((self.event_count, event));
+        self.event_count += 1;
+    }
+
+    /// Drop the events sent before the previous update
+    pub fn update(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// System running [`Events::update`]
+    pub fn update_system(mut events: ResMut<Self>) {
+        events.update();
+    }
+
+    /// Remove and return every stored event, oldest first
+    pub fn drain(&mut self) -> impl Iterator<Item = E> + '_ {
+        self.previous
+            .drain(..)
+            .chain(self.current.drain(..))
+            .map(|(_, event)| event)
+    }
+
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Number of stored events
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A reader that will see every event sent from now on
+    pub fn get_reader(&self) -> ManualEventReader<E> {
+        ManualEventReader {
+            last_event_count: self.event_count,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Tracks which events of an [`Events`] resource were already read
+pub struct ManualEventReader<E> {
+    last_event_count: usize,
+    marker: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for ManualEventReader<E> {
+    fn default() -> Self {
+        Self {
+            last_event_count: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<E: Event> ManualEventReader<E> {
+    /// The events not read yet, oldest first
+    pub fn read<'a>(&'a mut self, events: &'a Events<E>) -> impl Iterator<Item = &'a E> + 'a {
+        let last_event_count = self.last_event_count;
+        self.last_event_count = events.event_count;
+        events
+            .previous
+            .iter()
+            .chain(&events.current)
+            .filter(move |(id, _)| *id >= last_event_count)
+            .map(|(_, event)| event)
+    }
+
+    /// Number of events not read yet
+    pub fn len(&self, events: &Events<E>) -> usize {
+        events
+            .previous
+            .iter()
+            .chain(&events.current)
+            .filter(|(id, _)| *id >= self.last_event_count)
+            .count()
+    }
+
+    pub fn is_empty(&self, events: &Events<E>) -> bool {
+        self.len(events) == 0
+    }
+}
+
+/// Reads the events of type `E` sent since this system last ran
+pub struct EventReader<'w, 's, E: Event> {
+    reader: &'s mut ManualEventReader<E>,
+    events: Res<'w, Events<E>>,
+}
+
+impl<'w, 's, E: Event> EventReader<'w, 's, E> {
+    /// The events not read yet, oldest first
+    pub fn read(&mut self) -> impl Iterator<Item = &E> + '_ {
+        self.reader.read(&self.events)
+    }
+
+    pub fn len(&self) -> usize {
+        self.reader.len(&self.events)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reader.is_empty(&self.events)
+    }
+
+    /// Mark every event as read
+    pub fn clear(&mut self) {
+        self.reader.read(&self.events).for_each(drop);
+    }
+}
+
+impl<'a, 'b, E: Event> SystemParam for EventReader<'a, 'b, E> {
+    type State = ManualEventReader<E>;
+    type Item<'w, 's> = EventReader<'w, 's, E>;
+
+    fn init_state(world: &mut World, meta: &mut SystemMeta) -> ManualEventReader<E> {
+        <Res<Events<E>> as SystemParam>::init_state(world, meta);
+        ManualEventReader::default()
+    }
+
+    fn get_param<'w, 's>(
+        state: &'s mut ManualEventReader<E>,
+        meta: &SystemMeta,
+        world: &'w World,
+    ) -> EventReader<'w, 's, E> {
+        EventReader {
+            reader: state,
+            events: <Res<Events<E>> as SystemParam>::get_param(&mut (), meta, world),
+        }
+    }
+}
+
+/// Sends events of type `E`
+pub struct EventWriter<'w, E: Event> {
+    events: ResMut<'w, Events<E>>,
+}
+
+impl<'w, E: Event> EventWriter<'w, E> {
+    pub fn send(&mut self, event: E) {
+        self.events.send(event);
+    }
+}
+
+impl<'a, E: Event> SystemParam for EventWriter<'a, E> {
+    type State = ();
+    type Item<'w, 's> = EventWriter<'w, E>;
+
+    fn init_state(world: &mut World, meta: &mut SystemMeta) {
+        <ResMut<Events<E>> as SystemParam>::init_state(world, meta);
+    }
+
+    fn get_param<'w>(state: &mut (), meta: &SystemMeta, world: &'w World) -> EventWriter<'w, E> {
+        EventWriter {
+            events: <ResMut<Events<E>> as SystemParam>::get_param(state, meta, world),
+        }
+    }
+}
+
+impl World {
+    /// Send an event through its [`Events`] resource
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Events<E>` resource does not exist.
+    pub fn send_event<E: Event>(&mut self, event: E) {
+        self.resource_mut::<Events<E>>().send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    struct Ping(u32);
+
+    #[test]
+    fn events_live_for_two_updates() {
+        let mut events = Events::<Ping>::default();
+        let mut reader = events.get_reader();
+        events.send(Ping(0));
+        events.update();
+        events.send(Ping(1));
+        assert_eq!(
+            reader.read(&events).copied().collect::<Vec<_>>(),
+            [Ping(0), Ping(1)]
+        );
+        assert!(reader.is_empty(&events));
+
+        let mut late_reader = ManualEventReader::default();
+        events.update();
+        assert_eq!(
+            late_reader.read(&events).copied().collect::<Vec<_>>(),
+            [Ping(1)]
+        );
+        events.update();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn reader_and_writer_params() {
+        #[derive(Default)]
+        struct Received(Vec<Ping>);
+
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        struct TestSchedule;
+        impl ScheduleLabel for TestSchedule {}
+
+        let mut world = World::new();
+        world.init_resource::<Events<Ping>>();
+        world.init_resource::<Received>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems((
+            |mut reader: EventReader<Ping>, mut received: ResMut<Received>| {
+                received.0.extend(reader.read().copied());
+            },
+            |mut writer: EventWriter<Ping>, mut count: Local<u32>| {
+                writer.send(Ping(*count));
+                *count += 1;
+            },
+            Events::<Ping>::update_system,
+        ));
+        schedule.run(&mut world);
+        world.send_event(Ping(10));
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Received>().0, [Ping(0), Ping(10), Ping(1)]);
+    }
+}
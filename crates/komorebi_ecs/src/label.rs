@@ -0,0 +1,77 @@
+use std::any::{Any, TypeId};
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// Object-safe equality, hashing and downcasting shared by every label trait
+pub trait DynLabel: Debug + Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+    fn dyn_eq(&self, other: &dyn Any) -> bool;
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+}
+
+impl<T: Debug + Eq + Hash + Send + Sync + 'static> DynLabel for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<T>() == Some(self)
+    }
+
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<T>().hash(&mut state);
+        self.hash(&mut state);
+    }
+}
+
+/// Declares a label trait whose trait objects can be compared, hashed and used as map keys
+#[macro_export]
+macro_rules! define_label {
+    ($(#[$meta:meta])* $label_trait:ident) => {
+        $(#[$meta])*
+        pub trait $label_trait: $crate::DynLabel {}
+
+        impl PartialEq for dyn $label_trait {
+            fn eq(&self, other: &Self) -> bool {
+                self.dyn_eq(other.as_any())
+            }
+        }
+
+        impl Eq for dyn $label_trait {}
+
+        impl std::hash::Hash for dyn $label_trait {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.dyn_hash(state);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    define_label!(TestLabel);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct A;
+    impl TestLabel for A {}
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct B(u32);
+    impl TestLabel for B {}
+
+    #[test]
+    fn dyn_equality() {
+        let a: Arc<dyn TestLabel> = Arc::new(A);
+        assert!(a == Arc::new(A) as Arc<dyn TestLabel>);
+        assert!(a != Arc::new(B(0)) as Arc<dyn TestLabel>);
+        assert!(Arc::new(B(1)) as Arc<dyn TestLabel> != Arc::new(B(2)) as Arc<dyn TestLabel>);
+
+        let mut set = std::collections::HashSet::<Arc<dyn TestLabel>>::new();
+        set.insert(a.clone());
+        set.insert(Arc::new(B(1)));
+        assert!(set.contains(&a));
+        assert!(!set.contains(&(Arc::new(B(2)) as Arc<dyn TestLabel>)));
+    }
+}
@@ -1,6 +1,18 @@
+mod access;
+mod event;
+mod label;
+mod resource;
+mod schedule;
 mod storage;
+mod system;
 
+pub use access::*;
+pub use event::*;
+pub use label::*;
+pub use resource::*;
+pub use schedule::*;
 pub use storage::*;
+pub use system::*;
 
 use std::any::{type_name, TypeId};
 use std::sync::Mutex;
@@ -14,10 +26,18 @@ pub struct Entity {
     index: u32,
 }
 
+impl Entity {
+    /// The slot of this entity in component storages
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
 pub struct World {
     entities: BitSet,
     generations: Vec<u32>,
     storages: FxHashMap<TypeId, Mutex<Box<dyn AbstractStorage>>>,
+    resources: FxHashMap<TypeId, ResourceCell>,
 }
 
 impl World {
@@ -26,6 +46,7 @@ impl World {
             entities: BitSet::new(),
             generations: Vec::new(),
             storages: FxHashMap::default(),
+            resources: FxHashMap::default(),
         }
     }
 
@@ -40,6 +61,11 @@ impl World {
         );
     }
 
+    /// Whether storage `S` was registered
+    pub fn is_registered<S: Storage>(&self) -> bool {
+        self.storages.contains_key(&TypeId::of::<S>())
+    }
+
     /// Discard a type of storage, destroying its contents
     pub fn unregister<S: Storage>(&mut self) {
         self.storages.remove(&TypeId::of::<S>());
@@ -79,11 +105,37 @@ impl World {
             let mut storage = storage.try_lock().expect("storage already borrowed");
             storage.free(entity.index);
         }
+        self.entities.remove(entity.index);
         self.generations[entity.index as usize] =
             self.generations[entity.index as usize].wrapping_add(1);
         true
     }
 
+    /// Iterate over every live entity
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        (&self.entities).iter().map(move |index| Entity {
+            generation: self.generations[index as usize],
+            index,
+        })
+    }
+
+    /// Number of live entities
+    pub fn entity_count(&self) -> usize {
+        (&self.entities).iter().count()
+    }
+
+    /// Iterate over the type name and component count of every storage
+    ///
+    /// # Panics
+    ///
+    /// Panics if a storage is borrowed.
+    pub fn storage_counts(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.storages.values().map(|storage| {
+            let storage = storage.try_lock().expect("storage already borrowed");
+            (storage.name(), storage.component_count())
+        })
+    }
+
     /// Associate `component` with `entity`
     ///
     /// Return `Some` if there was pre-existing component for this entity in this storage.
@@ -109,6 +161,12 @@ impl World {
     }
 }
 
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub trait Fetch<'a> {
     type Ref;
     fn fetch(world: &'a World) -> Self::Ref;
@@ -185,6 +243,25 @@ mod tests {
         assert!(!world.contains(entity));
     }
 
+    #[test]
+    fn entity_reuse() {
+        let mut world = World::new();
+        world.register::<VecStorage<u32>>();
+        let a = world.spawn();
+        let b = world.spawn();
+        world.insert::<VecStorage<u32>>(a, 1);
+        assert!(world.despawn(a));
+        assert_eq!(world.entities().collect::<Vec<_>>(), [b]);
+
+        let c = world.spawn();
+        assert_eq!(c.index(), a.index());
+        assert!(!world.contains(a));
+        assert!(world.contains(c));
+        assert!(world.get::<VecStorage<u32>>().get(c.index()).is_none());
+        assert_eq!(world.insert::<VecStorage<u32>>(a, 2), None);
+        assert_eq!(world.entities().count(), 2);
+    }
+
     #[test]
     #[should_panic(expected = "already borrowed")]
     fn double_borrow() {
@@ -194,4 +271,12 @@ mod tests {
     }
 }
 
-pub mod prelude {}
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{
+        common_conditions::*, system_adapter, Condition, Entity, EventReader, EventWriter, Events,
+        In, IntoSystem, IntoSystemConfigs, IntoSystemSetConfig, IntoSystemSetConfigs, Local, Res,
+        ResMut, Schedule, ScheduleLabel, Schedules, StorageRef, StorageRefMut, System, SystemSet,
+        VecStorage, World,
+    };
+}
@@ -0,0 +1,140 @@
+use std::any::{type_name, Any, TypeId};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::World;
+
+/// A unique, global value stored in the [`World`]
+pub trait Resource: Send + 'static {}
+
+impl<T: Send + 'static> Resource for T {}
+
+pub(crate) type ResourceCell = Mutex<Box<dyn Any + Send>>;
+
+/// Read access to a [`Resource`]
+///
+/// The resource is locked while borrowed, so a system takes at most one `Res` or [`ResMut`]
+/// of each resource, which is checked when it is initialized.
+pub struct Res<'a, R> {
+    guard: MutexGuard<'a, Box<dyn Any + Send>>,
+    marker: PhantomData<R>,
+}
+
+impl<'a, R: Resource> Deref for Res<'a, R> {
+    type Target = R;
+    fn deref(&self) -> &R {
+        self.guard.downcast_ref::<R>().unwrap()
+    }
+}
+
+/// Unique access to a [`Resource`]
+pub struct ResMut<'a, R> {
+    guard: MutexGuard<'a, Box<dyn Any + Send>>,
+    marker: PhantomData<R>,
+}
+
+impl<'a, R: Resource> Deref for ResMut<'a, R> {
+    type Target = R;
+    fn deref(&self) -> &R {
+        self.guard.downcast_ref::<R>().unwrap()
+    }
+}
+
+impl<'a, R: Resource> DerefMut for ResMut<'a, R> {
+    fn deref_mut(&mut self) -> &mut R {
+        self.guard.downcast_mut::<R>().unwrap()
+    }
+}
+
+impl World {
+    /// Insert a resource, replacing any previous value of the same type
+    pub fn insert_resource<R: Resource>(&mut self, value: R) {
+        self.resources
+            .insert(TypeId::of::<R>(), Mutex::new(Box::new(value)));
+    }
+
+    /// Insert the default value of a resource, unless it already exists
+    pub fn init_resource<R: Resource + Default>(&mut self) {
+        if !self.contains_resource::<R>() {
+            self.insert_resource(R::default());
+        }
+    }
+
+    /// Remove a resource, returning its value
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        let cell = self.resources.remove(&TypeId::of::<R>())?;
+        let value = cell.into_inner().unwrap_or_else(|e| e.into_inner());
+        Some(*value.downcast::<R>().unwrap())
+    }
+
+    /// Whether a resource of type `R` exists
+    pub fn contains_resource<R: Resource>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    /// Access a resource
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource does not exist or is already borrowed.
+    pub fn resource<R: Resource>(&self) -> Res<'_, R> {
+        self.get_resource()
+            .unwrap_or_else(|| panic!("resource {} does not exist", type_name::<R>()))
+    }
+
+    /// Mutably access a resource
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource does not exist or is already borrowed.
+    pub fn resource_mut<R: Resource>(&self) -> ResMut<'_, R> {
+        self.get_resource_mut()
+            .unwrap_or_else(|| panic!("resource {} does not exist", type_name::<R>()))
+    }
+
+    /// Access a resource, if it exists
+    pub fn get_resource<R: Resource>(&self) -> Option<Res<'_, R>> {
+        Some(Res {
+            guard: self.lock_resource::<R>()?,
+            marker: PhantomData,
+        })
+    }
+
+    /// Mutably access a resource, if it exists
+    pub fn get_resource_mut<R: Resource>(&self) -> Option<ResMut<'_, R>> {
+        Some(ResMut {
+            guard: self.lock_resource::<R>()?,
+            marker: PhantomData,
+        })
+    }
+
+    /// Temporarily remove a resource so that it can be used alongside `&mut World`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource does not exist.
+    pub fn resource_scope<R: Resource, U>(&mut self, f: impl FnOnce(&mut World, &mut R) -> U) -> U {
+        let mut value = self
+            .remove_resource::<R>()
+            .unwrap_or_else(|| panic!("resource {} does not exist", type_name::<R>()));
+        let result = f(self, &mut value);
+        if self.contains_resource::<R>() {
+            panic!(
+                "resource {} was inserted while it was being scoped",
+                type_name::<R>()
+            );
+        }
+        self.insert_resource(value);
+        result
+    }
+
+    fn lock_resource<R: Resource>(&self) -> Option<MutexGuard<'_, Box<dyn Any + Send>>> {
+        let guard = self
+            .resources
+            .get(&TypeId::of::<R>())?
+            .try_lock()
+            .unwrap_or_else(|_| panic!("resource {} already borrowed", type_name::<R>()));
+        Some(guard)
+    }
+}
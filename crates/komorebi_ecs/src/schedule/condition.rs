@@ -0,0 +1,46 @@
+use crate::{BoxedSystem, IntoSystem};
+
+pub type BoxedCondition = BoxedSystem<(), bool>;
+
+/// A system returning `bool`, used to decide whether other systems run
+///
+/// See [`IntoSystemConfigs::run_if`](crate::IntoSystemConfigs::run_if).
+pub trait Condition<Marker>: IntoSystem<(), bool, Marker> {}
+
+impl<Marker, F: IntoSystem<(), bool, Marker>> Condition<Marker> for F {}
+
+pub mod common_conditions {
+    use crate::{Condition, In, Res, Resource, System};
+
+    /// Returns `true` the first time it is evaluated, and `false` afterwards
+    pub fn run_once() -> impl FnMut() -> bool + Send + 'static {
+        let mut has_run = false;
+        move || !std::mem::replace(&mut has_run, true)
+    }
+
+    /// Whether the resource `T` exists
+    pub fn resource_exists<T: Resource>() -> impl FnMut(Option<Res<T>>) -> bool {
+        |res: Option<Res<T>>| res.is_some()
+    }
+
+    /// Whether the resource `T` equals `value`
+    ///
+    /// # Panics
+    ///
+    /// The condition panics if the resource does not exist.
+    pub fn resource_equals<T: Resource + PartialEq>(value: T) -> impl FnMut(Res<T>) -> bool {
+        move |res: Res<T>| *res == value
+    }
+
+    /// Whether the resource `T` exists and equals `value`
+    pub fn resource_exists_and_equals<T: Resource + PartialEq>(
+        value: T,
+    ) -> impl FnMut(Option<Res<T>>) -> bool {
+        move |res: Option<Res<T>>| res.is_some_and(|res| *res == value)
+    }
+
+    /// Inverts the output of `condition`
+    pub fn not<Marker>(condition: impl Condition<Marker>) -> impl System<In = (), Out = bool> {
+        condition.pipe(|In(value): In<bool>| !value)
+    }
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use crate::{
+    BoxedCondition, BoxedSystem, Condition, GraphInfo, IntoSystem, IntoSystemSet, SystemSet,
+};
+
+/// Systems ready to be inserted into a [`Schedule`](crate::Schedule), together with their
+/// run conditions, sets and ordering
+pub struct SystemConfigs {
+    pub(crate) kind: SystemConfigsKind,
+    /// Sets and ordering shared by every system in this node
+    pub(crate) graph_info: GraphInfo,
+    /// Conditions shared by every system in this node
+    pub(crate) conditions: Vec<BoxedCondition>,
+}
+
+pub(crate) enum SystemConfigsKind {
+    System(BoxedSystem),
+    Group {
+        configs: Vec<SystemConfigs>,
+        /// Whether each element runs after the previous one
+        chained: bool,
+    },
+}
+
+/// Types that can be turned into [`SystemConfigs`]: systems, and tuples of them
+pub trait IntoSystemConfigs<Marker>: Sized {
+    fn into_configs(self) -> SystemConfigs;
+
+    /// Only run the systems when `condition` returns `true`
+    ///
+    /// On a group of systems, the condition is evaluated at most once per schedule run,
+    /// before the first system of the group.
+    fn run_if<M>(self, condition: impl Condition<M>) -> SystemConfigs {
+        let mut configs = self.into_configs();
+        configs
+            .conditions
+            .push(Box::new(IntoSystem::into_system(condition)));
+        configs
+    }
+
+    /// Add the systems to `set`
+    fn in_set(self, set: impl SystemSet) -> SystemConfigs {
+        let mut configs = self.into_configs();
+        configs.graph_info.sets.push(Arc::new(set));
+        configs
+    }
+
+    /// Run the systems before `set`, or before a system when given its function
+    fn before<M>(self, set: impl IntoSystemSet<M>) -> SystemConfigs {
+        let mut configs = self.into_configs();
+        configs
+            .graph_info
+            .before
+            .push(Arc::new(set.into_system_set()));
+        configs
+    }
+
+    /// Run the systems after `set`, or after a system when given its function
+    fn after<M>(self, set: impl IntoSystemSet<M>) -> SystemConfigs {
+        let mut configs = self.into_configs();
+        configs
+            .graph_info
+            .after
+            .push(Arc::new(set.into_system_set()));
+        configs
+    }
+
+    /// Run the elements of a tuple one after another, in the order they are written
+    fn chain(self) -> SystemConfigs {
+        let mut configs = self.into_configs();
+        if let SystemConfigsKind::Group { chained, .. } = &mut configs.kind {
+            *chained = true;
+        }
+        configs
+    }
+
+    /// Do not report ambiguities between these systems and those of `set`
+    fn ambiguous_with<M>(self, set: impl IntoSystemSet<M>) -> SystemConfigs {
+        let mut configs = self.into_configs();
+        configs
+            .graph_info
+            .ambiguous_with
+            .push(Arc::new(set.into_system_set()));
+        configs
+    }
+}
+
+impl<F, Marker> IntoSystemConfigs<Marker> for F
+where
+    F: IntoSystem<(), (), Marker>,
+{
+    fn into_configs(self) -> SystemConfigs {
+        SystemConfigs {
+            kind: SystemConfigsKind::System(Box::new(IntoSystem::into_system(self))),
+            graph_info: GraphInfo::default(),
+            conditions: Vec::new(),
+        }
+    }
+}
+
+impl IntoSystemConfigs<()> for SystemConfigs {
+    fn into_configs(self) -> SystemConfigs {
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct SystemConfigTupleMarker;
+
+macro_rules! impl_system_configs_tuple {
+    ($(($sys: ident, $marker: ident)),*) => {
+        #[allow(non_snake_case)]
+        impl<$($sys, $marker),*> IntoSystemConfigs<(SystemConfigTupleMarker, $($marker,)*)>
+            for ($($sys,)*)
+        where
+            $($sys: IntoSystemConfigs<$marker>),*
+        {
+            fn into_configs(self) -> SystemConfigs {
+                let ($($sys,)*) = self;
+                SystemConfigs {
+                    kind: SystemConfigsKind::Group {
+                        configs: vec![$($sys.into_configs(),)*],
+                        chained: false,
+                    },
+                    graph_info: GraphInfo::default(),
+                    conditions: Vec::new(),
+                }
+            }
+        }
+    };
+}
+
+impl_system_configs_tuple!((S0, M0));
+impl_system_configs_tuple!((S0, M0), (S1, M1));
+impl_system_configs_tuple!((S0, M0), (S1, M1), (S2, M2));
+impl_system_configs_tuple!((S0, M0), (S1, M1), (S2, M2), (S3, M3));
+impl_system_configs_tuple!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4));
+impl_system_configs_tuple!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4), (S5, M5));
+impl_system_configs_tuple!(
+    (S0, M0),
+    (S1, M1),
+    (S2, M2),
+    (S3, M3),
+    (S4, M4),
+    (S5, M5),
+    (S6, M6)
+);
+impl_system_configs_tuple!(
+    (S0, M0),
+    (S1, M1),
+    (S2, M2),
+    (S3, M3),
+    (S4, M4),
+    (S5, M5),
+    (S6, M6),
+    (S7, M7)
+);
@@ -0,0 +1,131 @@
+use std::fmt::{self, Write};
+use std::sync::Arc;
+
+use fxhash::FxHashMap;
+
+use crate::{GraphInfo, Schedule, SystemSet};
+
+/// Source nodes, target nodes and DOT attributes of the edges between them
+type Edge = (Vec<String>, Vec<String>, &'static str);
+
+const MEMBERSHIP: &str = "style=dashed";
+const ORDER: &str = "";
+const AMBIGUOUS: &str = "style=dotted, dir=none";
+
+/// Quote `s` as a DOT string
+pub fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Schedule {
+    /// The systems, sets and ordering of this schedule as a Graphviz DOT graph
+    ///
+    /// See [`Schedule::write_dot`] for how the graph is drawn.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph schedule {\n");
+        self.write_dot(&mut dot, "schedule")
+            .expect("writing to a String cannot fail");
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Write this schedule as a DOT `subgraph cluster_<id>`, to be embedded in a larger graph
+    ///
+    /// Systems are boxes and sets are dashed ellipses. Dashed edges lead from a system or set
+    /// to the sets containing it, solid edges go from what runs first to what runs after, and
+    /// dotted lines join systems allowed to be ambiguous. Node ids are prefixed with `id`.
+    pub fn write_dot(&self, out: &mut impl Write, id: &str) -> fmt::Result {
+        // Sets that stand for a single system are drawn as that system
+        let mut system_type_sets: FxHashMap<Arc<dyn SystemSet>, Vec<usize>> = FxHashMap::default();
+        for (index, node) in self.systems.iter().enumerate() {
+            for set in node.system.default_system_sets() {
+                system_type_sets.entry(set).or_default().push(index);
+            }
+        }
+        let mut sets: Vec<Arc<dyn SystemSet>> = Vec::new();
+        let mut set_ids: FxHashMap<Arc<dyn SystemSet>, usize> = FxHashMap::default();
+        let mut nodes = |set: &Arc<dyn SystemSet>| -> Vec<String> {
+            if let Some(systems) = system_type_sets.get(set) {
+                return systems
+                    .iter()
+                    .map(|index| format!("{id}_system_{index}"))
+                    .collect();
+            }
+            let set_id = *set_ids.entry(set.clone()).or_insert_with(|| {
+                sets.push(set.clone());
+                sets.len() - 1
+            });
+            vec![format!("{id}_set_{set_id}")]
+        };
+
+        let mut edges: Vec<Edge> = Vec::new();
+        for node in &self.sets {
+            let this = nodes(&node.set);
+            add_edges(&mut edges, &mut nodes, &this, &node.graph_info);
+        }
+        for (index, node) in self.systems.iter().enumerate() {
+            let this = vec![format!("{id}_system_{index}")];
+            add_edges(&mut edges, &mut nodes, &this, &node.graph_info);
+        }
+        for &(a, b) in &self.chain_edges {
+            edges.push((
+                vec![format!("{id}_system_{a}")],
+                vec![format!("{id}_system_{b}")],
+                ORDER,
+            ));
+        }
+
+        writeln!(out, "    subgraph cluster_{id} {{")?;
+        writeln!(
+            out,
+            "        label={};",
+            dot_quote(&format!("{:?}", self.label))
+        )?;
+        for (index, node) in self.systems.iter().enumerate() {
+            writeln!(
+                out,
+                "        {id}_system_{index} [label={}, shape=box];",
+                dot_quote(&node.system.name())
+            )?;
+        }
+        for (set_id, set) in sets.iter().enumerate() {
+            writeln!(
+                out,
+                "        {id}_set_{set_id} [label={}, style=dashed];",
+                dot_quote(&format!("{set:?}"))
+            )?;
+        }
+        for (from, to, attributes) in edges {
+            for a in &from {
+                for b in &to {
+                    if attributes.is_empty() {
+                        writeln!(out, "        {a} -> {b};")?;
+                    } else {
+                        writeln!(out, "        {a} -> {b} [{attributes}];")?;
+                    }
+                }
+            }
+        }
+        writeln!(out, "    }}")
+    }
+}
+
+fn add_edges(
+    edges: &mut Vec<Edge>,
+    nodes: &mut impl FnMut(&Arc<dyn SystemSet>) -> Vec<String>,
+    this: &[String],
+    info: &GraphInfo,
+) {
+    for set in &info.sets {
+        edges.push((this.to_vec(), nodes(set), MEMBERSHIP));
+    }
+    for set in &info.before {
+        edges.push((this.to_vec(), nodes(set), ORDER));
+    }
+    for set in &info.after {
+        edges.push((nodes(set), this.to_vec(), ORDER));
+    }
+    for set in &info.ambiguous_with {
+        edges.push((this.to_vec(), nodes(set), AMBIGUOUS));
+    }
+}
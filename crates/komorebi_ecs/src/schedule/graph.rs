@@ -0,0 +1,111 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Sort `0..node_count` so that for every `(a, b)` in `edges`, `a` comes before `b`
+///
+/// Nodes that are not ordered relative to each other keep their index order. On failure the
+/// nodes of one cycle are returned, in edge order.
+pub(crate) fn topological_sort(
+    node_count: usize,
+    edges: &[(usize, usize)],
+) -> Result<Vec<usize>, Vec<usize>> {
+    let mut successors = vec![Vec::new(); node_count];
+    let mut in_degree = vec![0usize; node_count];
+    for &(a, b) in edges {
+        successors[a].push(b);
+        in_degree[b] += 1;
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..node_count)
+        .filter(|&node| in_degree[node] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(node_count);
+    while let Some(Reverse(node)) = ready.pop() {
+        order.push(node);
+        for &next in &successors[node] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+    if order.len() == node_count {
+        return Ok(order);
+    }
+
+    // Every node left over has a predecessor that is also left over, so walking predecessors
+    // from any of them must come back to a node already seen
+    let mut predecessors = vec![Vec::new(); node_count];
+    for &(a, b) in edges {
+        if in_degree[a] > 0 && in_degree[b] > 0 {
+            predecessors[b].push(a);
+        }
+    }
+    let mut node = (0..node_count).find(|&node| in_degree[node] > 0).unwrap();
+    let mut path = Vec::new();
+    loop {
+        if let Some(start) = path.iter().position(|&seen| seen == node) {
+            let mut cycle = path.split_off(start);
+            cycle.reverse();
+            return Err(cycle);
+        }
+        path.push(node);
+        node = predecessors[node][0];
+    }
+}
+
+/// For every node, whether each other node can be reached from it
+///
+/// `order` must be a topological order of `edges`.
+pub(crate) fn reachability(
+    node_count: usize,
+    edges: &[(usize, usize)],
+    order: &[usize],
+) -> Vec<Vec<bool>> {
+    let mut successors = vec![Vec::new(); node_count];
+    for &(a, b) in edges {
+        successors[a].push(b);
+    }
+    let mut reachable = vec![vec![false; node_count]; node_count];
+    for &node in order.iter().rev() {
+        for &next in &successors[node] {
+            let from_next = reachable[next].clone();
+            for (reached, from_next) in reachable[node].iter_mut().zip(from_next) {
+                *reached |= from_next;
+            }
+            reachable[node][next] = true;
+        }
+    }
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_keeps_index_order() {
+        assert_eq!(topological_sort(4, &[(3, 1)]), Ok(vec![0, 2, 3, 1]));
+    }
+
+    #[test]
+    fn sort_reports_cycle() {
+        let cycle = topological_sort(4, &[(0, 1), (1, 2), (2, 3), (3, 1)]).unwrap_err();
+        // Any rotation of the cycle is a valid report
+        assert_eq!(cycle.len(), 3);
+        for (i, &node) in cycle.iter().enumerate() {
+            let next = cycle[(i + 1) % cycle.len()];
+            assert!([(1, 2), (2, 3), (3, 1)].contains(&(node, next)));
+        }
+    }
+
+    #[test]
+    fn reachable_through_chain() {
+        let edges = [(0, 1), (1, 2)];
+        let reachable = reachability(4, &edges, &[0, 1, 2, 3]);
+        assert!(reachable[0][2]);
+        assert!(!reachable[2][0]);
+        assert!(!reachable[0][3]);
+    }
+}
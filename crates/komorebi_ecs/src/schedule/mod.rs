@@ -0,0 +1,1038 @@
+mod condition;
+mod config;
+mod dot;
+mod graph;
+mod set;
+
+pub use condition::*;
+pub use config::*;
+pub use dot::*;
+pub use set::*;
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use fxhash::{FxHashMap, FxHashSet};
+use komorebi_utils::tracing::warn;
+
+use crate::{define_label, BoxedSystem, System, World};
+
+define_label!(
+    /// A label identifying a [`Schedule`] in [`Schedules`]
+    ScheduleLabel
+);
+
+/// A collection of systems, run one after another
+///
+/// Systems run in the order they were added, unless reordered by
+/// [`before`](IntoSystemConfigs::before), [`after`](IntoSystemConfigs::after),
+/// [`chain`](IntoSystemConfigs::chain) or the ordering of their [`SystemSet`]s.
+pub struct Schedule {
+    label: Arc<dyn ScheduleLabel>,
+    systems: Vec<SystemNode>,
+    sets: Vec<SetNode>,
+    set_ids: FxHashMap<Arc<dyn SystemSet>, usize>,
+    conditions: Vec<BoxedCondition>,
+    /// Edges from [`chain`](IntoSystemConfigs::chain), as `(before, after)` system indices
+    chain_edges: Vec<(usize, usize)>,
+    /// Indices into `systems`, in the order they run
+    order: Vec<usize>,
+    ambiguities: Vec<SystemAmbiguity>,
+    /// Names the systems are recorded under in [`SystemTimings`], unique within the schedule
+    timing_names: Vec<Cow<'static, str>>,
+    settings: ScheduleBuildSettings,
+    /// Whether systems or sets changed since the order was last built
+    dirty: bool,
+    /// Number of systems, from the front of `systems`, already initialized
+    initialized_systems: usize,
+    /// Number of conditions, from the front of `conditions`, already initialized
+    initialized_conditions: usize,
+}
+
+struct SystemNode {
+    system: BoxedSystem,
+    graph_info: GraphInfo,
+    /// Indices into `Schedule::conditions` added with the system or its groups
+    conditions: Vec<usize>,
+    /// `conditions` plus those of every set the system is in, filled in by the build
+    all_conditions: Vec<usize>,
+}
+
+struct SetNode {
+    set: Arc<dyn SystemSet>,
+    graph_info: GraphInfo,
+    conditions: Vec<usize>,
+}
+
+/// How [`Schedule::build`] reacts to what it finds
+#[derive(Clone, Debug, Default)]
+pub struct ScheduleBuildSettings {
+    /// What to do about pairs of unordered systems with conflicting access
+    pub ambiguity_detection: LogLevel,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Do nothing
+    #[default]
+    Ignore,
+    /// Log a warning
+    Warn,
+    /// Fail the build with an error
+    Error,
+}
+
+/// Two systems that are not ordered relative to each other, but access the same data and
+/// at least one of them writes it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemAmbiguity {
+    pub first: Cow<'static, str>,
+    pub second: Cow<'static, str>,
+    /// Names of the resources and storages both systems access
+    pub conflicts: Vec<&'static str>,
+}
+
+impl fmt::Display for SystemAmbiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} and {} conflict on {}",
+            self.first,
+            self.second,
+            self.conflicts.join(", ")
+        )
+    }
+}
+
+/// Error returned by [`Schedule::build`]
+#[derive(Debug)]
+pub enum ScheduleBuildError {
+    /// Sets contain each other; the names of the sets in the cycle
+    HierarchyCycle(Vec<String>),
+    /// Systems must run before themselves; the names of the systems in the cycle
+    DependencyCycle(Vec<String>),
+    /// Ambiguities found with [`LogLevel::Error`] detection
+    Ambiguity(Vec<SystemAmbiguity>),
+}
+
+impl fmt::Display for ScheduleBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn cycle(names: &[String]) -> String {
+            let mut path = names.join(" -> ");
+            path.push_str(" -> ");
+            path.push_str(&names[0]);
+            path
+        }
+        match self {
+            Self::HierarchyCycle(names) => {
+                write!(f, "system set hierarchy contains a cycle: {}", cycle(names))
+            }
+            Self::DependencyCycle(names) => {
+                write!(f, "system ordering contains a cycle: {}", cycle(names))
+            }
+            Self::Ambiguity(ambiguities) => {
+                write!(f, "{} ambiguities found:", ambiguities.len())?;
+                for ambiguity in ambiguities {
+                    write!(f, "\n  {ambiguity}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleBuildError {}
+
+impl Schedule {
+    pub fn new(label: impl ScheduleLabel) -> Self {
+        Self {
+            label: Arc::new(label),
+            systems: Vec::new(),
+            sets: Vec::new(),
+            set_ids: FxHashMap::default(),
+            conditions: Vec::new(),
+            chain_edges: Vec::new(),
+            order: Vec::new(),
+            ambiguities: Vec::new(),
+            timing_names: Vec::new(),
+            settings: ScheduleBuildSettings::default(),
+            dirty: false,
+            initialized_systems: 0,
+            initialized_conditions: 0,
+        }
+    }
+
+    pub fn label(&self) -> &Arc<dyn ScheduleLabel> {
+        &self.label
+    }
+
+    /// Add systems to the schedule
+    pub fn add_systems<M>(&mut self, systems: impl IntoSystemConfigs<M>) -> &mut Self {
+        self.add_configs(systems.into_configs(), &mut Vec::new());
+        self.dirty = true;
+        self
+    }
+
+    /// Returns the indices of the systems added
+    fn add_configs(
+        &mut self,
+        configs: SystemConfigs,
+        inherited_conditions: &mut Vec<usize>,
+    ) -> Vec<usize> {
+        let inherited_len = inherited_conditions.len();
+        for condition in configs.conditions {
+            inherited_conditions.push(self.conditions.len());
+            self.conditions.push(condition);
+        }
+        let added = match configs.kind {
+            SystemConfigsKind::System(system) => {
+                self.systems.push(SystemNode {
+                    system,
+                    graph_info: GraphInfo::default(),
+                    conditions: inherited_conditions.clone(),
+                    all_conditions: Vec::new(),
+                });
+                vec![self.systems.len() - 1]
+            }
+            SystemConfigsKind::Group { configs, chained } => {
+                let mut added = Vec::new();
+                let mut previous: Vec<usize> = Vec::new();
+                for configs in configs {
+                    let current = self.add_configs(configs, inherited_conditions);
+                    if chained {
+                        for &a in &previous {
+                            for &b in &current {
+                                self.chain_edges.push((a, b));
+                            }
+                        }
+                    }
+                    added.extend_from_slice(&current);
+                    previous = current;
+                }
+                added
+            }
+        };
+        for &index in &added {
+            self.systems[index]
+                .graph_info
+                .extend(configs.graph_info.clone());
+        }
+        inherited_conditions.truncate(inherited_len);
+        added
+    }
+
+    /// Configure the membership, ordering and run conditions of system sets
+    pub fn configure_sets<M>(&mut self, sets: impl IntoSystemSetConfigs<M>) -> &mut Self {
+        for config in sets.into_configs() {
+            let conditions: Vec<usize> = config
+                .conditions
+                .into_iter()
+                .map(|condition| {
+                    self.conditions.push(condition);
+                    self.conditions.len() - 1
+                })
+                .collect();
+            let id = self.set_id(config.set);
+            self.sets[id].graph_info.extend(config.graph_info);
+            self.sets[id].conditions.extend(conditions);
+        }
+        self.dirty = true;
+        self
+    }
+
+    fn set_id(&mut self, set: Arc<dyn SystemSet>) -> usize {
+        if let Some(&id) = self.set_ids.get(&set) {
+            return id;
+        }
+        self.sets.push(SetNode {
+            set: set.clone(),
+            graph_info: GraphInfo::default(),
+            conditions: Vec::new(),
+        });
+        self.set_ids.insert(set, self.sets.len() - 1);
+        self.sets.len() - 1
+    }
+
+    pub fn set_build_settings(&mut self, settings: ScheduleBuildSettings) -> &mut Self {
+        self.settings = settings;
+        self.dirty = true;
+        self
+    }
+
+    pub fn build_settings(&self) -> &ScheduleBuildSettings {
+        &self.settings
+    }
+
+    /// Names of the systems, in the order they run once the schedule is built
+    pub fn system_names(&self) -> impl Iterator<Item = Cow<'static, str>> + '_ {
+        let order: Box<dyn Iterator<Item = usize>> = if self.dirty {
+            Box::new(0..self.systems.len())
+        } else {
+            Box::new(self.order.iter().copied())
+        };
+        order.map(|index| self.systems[index].system.name())
+    }
+
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Ambiguities found by the last [`build`](Self::build)
+    pub fn ambiguities(&self) -> &[SystemAmbiguity] {
+        &self.ambiguities
+    }
+
+    /// Initialize the systems and conditions added since the last call
+    pub fn initialize(&mut self, world: &mut World) {
+        for node in &mut self.systems[self.initialized_systems..] {
+            node.system.initialize(world);
+        }
+        self.initialized_systems = self.systems.len();
+        for condition in &mut self.conditions[self.initialized_conditions..] {
+            condition.initialize(world);
+        }
+        self.initialized_conditions = self.conditions.len();
+    }
+
+    /// Initialize new systems, and work out the order systems run in if anything changed
+    ///
+    /// Ambiguities between systems are looked for as set in the [`ScheduleBuildSettings`].
+    pub fn build(&mut self, world: &mut World) -> Result<(), ScheduleBuildError> {
+        self.initialize(world);
+        if !self.dirty {
+            return Ok(());
+        }
+
+        // Sets named anywhere but never configured still need an id
+        for index in 0..self.systems.len() {
+            let node = &self.systems[index];
+            let mut named = node.system.default_system_sets();
+            named.extend(node.graph_info.sets.iter().cloned());
+            named.extend(node.graph_info.before.iter().cloned());
+            named.extend(node.graph_info.after.iter().cloned());
+            named.extend(node.graph_info.ambiguous_with.iter().cloned());
+            for set in named {
+                self.set_id(set);
+            }
+        }
+        for index in 0..self.sets.len() {
+            let info = &self.sets[index].graph_info;
+            let named: Vec<_> = info
+                .sets
+                .iter()
+                .chain(&info.before)
+                .chain(&info.after)
+                .chain(&info.ambiguous_with)
+                .cloned()
+                .collect();
+            for set in named {
+                self.set_id(set);
+            }
+        }
+
+        // Sets in topological order, each before the sets containing it
+        let set_ids = &self.set_ids;
+        let hierarchy: Vec<(usize, usize)> = self
+            .sets
+            .iter()
+            .enumerate()
+            .flat_map(|(child, node)| {
+                node.graph_info
+                    .sets
+                    .iter()
+                    .map(move |parent| (child, set_ids[parent]))
+            })
+            .collect();
+        graph::topological_sort(self.sets.len(), &hierarchy).map_err(|cycle| {
+            ScheduleBuildError::HierarchyCycle(
+                cycle
+                    .into_iter()
+                    .map(|id| format!("{:?}", self.sets[id].set))
+                    .collect(),
+            )
+        })?;
+        let mut set_ancestors: Vec<FxHashSet<usize>> = vec![FxHashSet::default(); self.sets.len()];
+        for (id, ancestors) in set_ancestors.iter_mut().enumerate() {
+            let mut stack = vec![id];
+            while let Some(set) = stack.pop() {
+                if ancestors.insert(set) {
+                    stack.extend(
+                        self.sets[set]
+                            .graph_info
+                            .sets
+                            .iter()
+                            .map(|p| self.set_ids[p]),
+                    );
+                }
+            }
+        }
+
+        // Every set each system is in, directly or through nested sets
+        let mut system_sets: Vec<FxHashSet<usize>> = Vec::with_capacity(self.systems.len());
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); self.sets.len()];
+        for (index, node) in self.systems.iter().enumerate() {
+            let mut sets = FxHashSet::default();
+            for set in node
+                .system
+                .default_system_sets()
+                .iter()
+                .chain(&node.graph_info.sets)
+            {
+                sets.extend(set_ancestors[self.set_ids[set]].iter().copied());
+            }
+            for &set in &sets {
+                members[set].push(index);
+            }
+            system_sets.push(sets);
+        }
+
+        for (index, node) in self.systems.iter_mut().enumerate() {
+            let mut sets: Vec<usize> = system_sets[index].iter().copied().collect();
+            sets.sort_unstable();
+            node.all_conditions = node.conditions.clone();
+            for set in sets {
+                node.all_conditions
+                    .extend_from_slice(&self.sets[set].conditions);
+            }
+        }
+
+        let mut edges = self.chain_edges.clone();
+        let mut add_edges = |before: &[usize], after: &[usize]| {
+            for &a in before {
+                for &b in after {
+                    edges.push((a, b));
+                }
+            }
+        };
+        for (index, node) in self.systems.iter().enumerate() {
+            for set in &node.graph_info.before {
+                add_edges(&[index], &members[self.set_ids[set]]);
+            }
+            for set in &node.graph_info.after {
+                add_edges(&members[self.set_ids[set]], &[index]);
+            }
+        }
+        for (id, node) in self.sets.iter().enumerate() {
+            for set in &node.graph_info.before {
+                add_edges(&members[id], &members[self.set_ids[set]]);
+            }
+            for set in &node.graph_info.after {
+                add_edges(&members[self.set_ids[set]], &members[id]);
+            }
+        }
+
+        self.order = graph::topological_sort(self.systems.len(), &edges).map_err(|cycle| {
+            ScheduleBuildError::DependencyCycle(
+                cycle
+                    .into_iter()
+                    .map(|index| self.systems[index].system.name().into_owned())
+                    .collect(),
+            )
+        })?;
+
+        self.ambiguities = if self.settings.ambiguity_detection == LogLevel::Ignore {
+            Vec::new()
+        } else {
+            let ambiguous_with: Vec<FxHashSet<usize>> = self
+                .systems
+                .iter()
+                .enumerate()
+                .map(|(index, node)| {
+                    let own = node.graph_info.ambiguous_with.iter();
+                    let from_sets = system_sets[index]
+                        .iter()
+                        .flat_map(|&set| self.sets[set].graph_info.ambiguous_with.iter());
+                    own.chain(from_sets)
+                        .flat_map(|set| members[self.set_ids[set]].iter().copied())
+                        .collect()
+                })
+                .collect();
+            let reachable = graph::reachability(self.systems.len(), &edges, &self.order);
+            let mut ambiguities = Vec::new();
+            for (i, &a) in self.order.iter().enumerate() {
+                for &b in &self.order[i + 1..] {
+                    if reachable[a][b]
+                        || reachable[b][a]
+                        || ambiguous_with[a].contains(&b)
+                        || ambiguous_with[b].contains(&a)
+                    {
+                        continue;
+                    }
+                    let (first, second) = (&self.systems[a].system, &self.systems[b].system);
+                    let conflicts = first.access().conflicts(second.access());
+                    if !conflicts.is_empty() {
+                        ambiguities.push(SystemAmbiguity {
+                            first: first.name(),
+                            second: second.name(),
+                            conflicts,
+                        });
+                    }
+                }
+            }
+            ambiguities
+        };
+
+        // Systems sharing a name, like closures defined in the same function, are told apart
+        // by their index
+        let mut name_counts: FxHashMap<Cow<'static, str>, usize> = FxHashMap::default();
+        for node in &self.systems {
+            *name_counts.entry(node.system.name()).or_default() += 1;
+        }
+        self.timing_names = self
+            .systems
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let name = node.system.name();
+                match name_counts[&name] {
+                    1 => name,
+                    _ => format!("{name} #{index}").into(),
+                }
+            })
+            .collect();
+
+        if !self.ambiguities.is_empty() {
+            match self.settings.ambiguity_detection {
+                LogLevel::Ignore => {}
+                LogLevel::Warn => {
+                    let error = ScheduleBuildError::Ambiguity(self.ambiguities.clone());
+                    warn!("schedule {:?}: {error}", self.label);
+                }
+                LogLevel::Error => {
+                    return Err(ScheduleBuildError::Ambiguity(self.ambiguities.clone()));
+                }
+            }
+        }
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Run every system whose conditions hold once
+    ///
+    /// Exclusive systems run with `&mut World`, and therefore alone. Each condition is
+    /// evaluated at most once per run, even when shared by several systems.
+    ///
+    /// With a [`SystemTimings`] resource, the time each system ran for is recorded in it, under
+    /// its name followed by its index in the schedule when other systems have the same name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule fails to [`build`](Self::build).
+    pub fn run(&mut self, world: &mut World) {
+        if let Err(e) = self.build(world) {
+            panic!("failed to build schedule {:?}: {e}", self.label);
+        }
+        #[cfg(feature = "trace")]
+        let _schedule_span =
+            komorebi_utils::tracing::info_span!("schedule", name = ?self.label).entered();
+        let measure = world.contains_resource::<SystemTimings>();
+        let mut timings = Vec::new();
+        let mut condition_results = vec![None; self.conditions.len()];
+        for &index in &self.order {
+            let node = &mut self.systems[index];
+            let should_run = node.all_conditions.iter().all(|&id| {
+                *condition_results[id]
+                    .get_or_insert_with(|| run_system(&mut *self.conditions[id], world))
+            });
+            if should_run {
+                #[cfg(feature = "trace")]
+                let _system_span =
+                    komorebi_utils::tracing::info_span!("system", name = &*node.system.name())
+                        .entered();
+                let start = measure.then(Instant::now);
+                let running = RunningSystem::enter(node.system.name());
+                run_system(&mut *node.system, world);
+                drop(running);
+                if let Some(start) = start {
+                    timings.push((self.timing_names[index].clone(), start.elapsed()));
+                }
+            }
+        }
+        // Measured systems may have removed the resource
+        if let Some(mut system_timings) = world.get_resource_mut::<SystemTimings>() {
+            for (name, duration) in timings {
+                system_timings.record(name, duration);
+            }
+        }
+    }
+}
+
+/// Resource collecting how long systems ran for, when present in the [`World`]
+///
+/// Systems running several times per update, like fixed timestep systems catching up, add up
+/// until the timings are [taken](SystemTimings::take).
+#[derive(Debug, Default)]
+pub struct SystemTimings {
+    timings: FxHashMap<Cow<'static, str>, Duration>,
+}
+
+impl SystemTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `duration` to the time of the system called `name`
+    pub fn record(&mut self, name: Cow<'static, str>, duration: Duration) {
+        *self.timings.entry(name).or_default() += duration;
+    }
+
+    /// The time of each system since the last [`take`](SystemTimings::take)
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Duration)> {
+        self.timings
+            .iter()
+            .map(|(name, &duration)| (&**name, duration))
+    }
+
+    /// Remove the recorded timings, returning them
+    pub fn take(&mut self) -> FxHashMap<Cow<'static, str>, Duration> {
+        std::mem::take(&mut self.timings)
+    }
+}
+
+thread_local! {
+    static RUNNING_SYSTEM: RefCell<Option<Cow<'static, str>>> = const { RefCell::new(None) };
+}
+
+/// Name of the system running on the current thread, for panic reports
+pub fn running_system() -> Option<Cow<'static, str>> {
+    RUNNING_SYSTEM.with_borrow(|name| name.clone())
+}
+
+/// Sets [`running_system`] until dropped, also when a panicking system unwinds
+struct RunningSystem(Option<Cow<'static, str>>);
+
+impl RunningSystem {
+    fn enter(name: Cow<'static, str>) -> Self {
+        Self(RUNNING_SYSTEM.replace(Some(name)))
+    }
+}
+
+impl Drop for RunningSystem {
+    fn drop(&mut self) {
+        RUNNING_SYSTEM.set(self.0.take());
+    }
+}
+
+fn run_system<Out: 'static>(system: &mut dyn System<In = (), Out = Out>, world: &mut World) -> Out {
+    if system.is_exclusive() {
+        system.run((), world)
+    } else {
+        system.run_shared((), world)
+    }
+}
+
+/// Resource holding every [`Schedule`] of a [`World`], by label
+#[derive(Default)]
+pub struct Schedules {
+    inner: FxHashMap<Arc<dyn ScheduleLabel>, Schedule>,
+}
+
+impl Schedules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a schedule under its label, returning the schedule previously stored there
+    pub fn insert(&mut self, schedule: Schedule) -> Option<Schedule> {
+        self.inner.insert(schedule.label.clone(), schedule)
+    }
+
+    pub fn remove(&mut self, label: &dyn ScheduleLabel) -> Option<Schedule> {
+        self.inner.remove(label)
+    }
+
+    pub fn contains(&self, label: &dyn ScheduleLabel) -> bool {
+        self.inner.contains_key(label)
+    }
+
+    pub fn get(&self, label: &dyn ScheduleLabel) -> Option<&Schedule> {
+        self.inner.get(label)
+    }
+
+    pub fn get_mut(&mut self, label: &dyn ScheduleLabel) -> Option<&mut Schedule> {
+        self.inner.get_mut(label)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&dyn ScheduleLabel, &Schedule)> {
+        self.inner
+            .iter()
+            .map(|(label, schedule)| (&**label, schedule))
+    }
+}
+
+/// Error returned by [`World::try_run_schedule`] when the schedule does not exist
+#[derive(Debug)]
+pub struct MissingScheduleError {
+    /// Debug representation of the missing label
+    pub label: String,
+}
+
+impl fmt::Display for MissingScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "schedule {} does not exist", self.label)
+    }
+}
+
+impl std::error::Error for MissingScheduleError {}
+
+impl World {
+    /// Run the schedule stored under `label` in [`Schedules`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule does not exist.
+    pub fn run_schedule(&mut self, label: impl ScheduleLabel) {
+        self.run_schedule_ref(&label);
+    }
+
+    /// Same as [`World::run_schedule`], for a label already behind a reference
+    pub fn run_schedule_ref(&mut self, label: &dyn ScheduleLabel) {
+        if let Err(e) = self.try_run_schedule_ref(label) {
+            panic!("{e}");
+        }
+    }
+
+    /// Run the schedule stored under `label`, if it exists
+    pub fn try_run_schedule(
+        &mut self,
+        label: impl ScheduleLabel,
+    ) -> Result<(), MissingScheduleError> {
+        self.try_run_schedule_ref(&label)
+    }
+
+    /// Same as [`World::try_run_schedule`], for a label already behind a reference
+    pub fn try_run_schedule_ref(
+        &mut self,
+        label: &dyn ScheduleLabel,
+    ) -> Result<(), MissingScheduleError> {
+        // The schedule is taken out while it runs so that its systems can access `Schedules`
+        let schedule = self
+            .get_resource_mut::<Schedules>()
+            .and_then(|mut schedules| schedules.remove(label));
+        let Some(mut schedule) = schedule else {
+            return Err(MissingScheduleError {
+                label: format!("{label:?}"),
+            });
+        };
+        schedule.run(self);
+        self.init_resource::<Schedules>();
+        self.resource_mut::<Schedules>().insert(schedule);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct TestSchedule;
+    impl ScheduleLabel for TestSchedule {}
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    #[test]
+    fn runs_in_insertion_order() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems((
+            |mut log: ResMut<Log>| log.0.push("a"),
+            |world: &mut World| world.resource_mut::<Log>().0.push("exclusive"),
+            |mut log: ResMut<Log>| log.0.push("b"),
+        ));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().0, ["a", "exclusive", "b"]);
+    }
+
+    #[test]
+    fn run_schedule_from_schedules() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems(|world: &mut World| {
+            // The running schedule is not visible while it runs
+            let running = world.resource::<Schedules>().contains(&TestSchedule);
+            world
+                .resource_mut::<Log>()
+                .0
+                .push(if running { "visible" } else { "hidden" });
+        });
+        world.init_resource::<Schedules>();
+        world.resource_mut::<Schedules>().insert(schedule);
+
+        world.run_schedule(TestSchedule);
+        world.run_schedule(TestSchedule);
+        assert_eq!(world.resource::<Log>().0, ["hidden", "hidden"]);
+        assert!(world.resource::<Schedules>().contains(&TestSchedule));
+    }
+
+    #[test]
+    fn run_conditions() {
+        #[derive(PartialEq)]
+        struct Enabled(bool);
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.insert_resource(Enabled(false));
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems((
+            (|mut log: ResMut<Log>| log.0.push("a")).run_if(|enabled: Res<Enabled>| enabled.0),
+            (
+                |mut log: ResMut<Log>| log.0.push("b"),
+                |mut log: ResMut<Log>| log.0.push("c"),
+            )
+                .run_if(common_conditions::not(common_conditions::resource_equals(
+                    Enabled(true),
+                ))),
+            (|mut log: ResMut<Log>| log.0.push("once")).run_if(common_conditions::run_once()),
+        ));
+
+        schedule.run(&mut world);
+        world.resource_mut::<Enabled>().0 = true;
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().0, ["b", "c", "once", "a"]);
+    }
+
+    #[test]
+    fn group_condition_is_evaluated_once() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems(
+            (
+                |mut log: ResMut<Log>| log.0.push("a"),
+                |mut log: ResMut<Log>| log.0.push("b"),
+            )
+                .run_if(|mut log: ResMut<Log>| {
+                    log.0.push("condition");
+                    true
+                }),
+        );
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().0, ["condition", "a", "b"]);
+    }
+
+    fn push_a(mut log: ResMut<Log>) {
+        log.0.push("a");
+    }
+
+    fn push_b(mut log: ResMut<Log>) {
+        log.0.push("b");
+    }
+
+    fn push_c(mut log: ResMut<Log>) {
+        log.0.push("c");
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum TestSet {
+        First,
+        Second,
+    }
+    impl SystemSet for TestSet {}
+
+    #[test]
+    fn before_and_after() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems((push_a.after(push_c), push_b.before(push_a), push_c));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().0, ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn chain() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems(push_a.after(push_b));
+        schedule.add_systems((push_c, push_b).chain());
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().0, ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn sets() {
+        #[derive(PartialEq)]
+        struct Enabled(bool);
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.insert_resource(Enabled(false));
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.configure_sets((
+            TestSet::Second.after(TestSet::First),
+            TestSet::First.run_if(|enabled: Res<Enabled>| enabled.0),
+        ));
+        schedule.add_systems((
+            push_a.in_set(TestSet::Second),
+            (push_b, push_c).in_set(TestSet::First),
+        ));
+        schedule.run(&mut world);
+        world.resource_mut::<Enabled>().0 = true;
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().0, ["a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn cycle_names_systems() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems((push_a.before(push_b), push_b.before(push_a), push_c));
+        let error = schedule.build(&mut world).unwrap_err().to_string();
+        assert!(error.contains("cycle"), "{error}");
+        assert!(error.contains("push_a -> "), "{error}");
+        assert!(error.contains("push_b -> "), "{error}");
+        assert!(!error.contains("push_c"), "{error}");
+    }
+
+    #[test]
+    fn set_hierarchy_cycle() {
+        let mut world = World::new();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.configure_sets((
+            TestSet::First.in_set(TestSet::Second),
+            TestSet::Second.in_set(TestSet::First),
+        ));
+        assert!(matches!(
+            schedule.build(&mut world),
+            Err(ScheduleBuildError::HierarchyCycle(_))
+        ));
+    }
+
+    #[test]
+    fn ambiguities() {
+        fn read_log(_log: Res<Log>) {}
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Error,
+        });
+        schedule.add_systems((push_a, push_b.after(push_a), read_log, push_c));
+        let Err(ScheduleBuildError::Ambiguity(ambiguities)) = schedule.build(&mut world) else {
+            panic!("expected ambiguities");
+        };
+        let pairs: Vec<_> = ambiguities
+            .iter()
+            .map(|a| (a.first.rsplit("::").next(), a.second.rsplit("::").next()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                (Some("push_a"), Some("read_log")),
+                (Some("push_a"), Some("push_c")),
+                (Some("push_b"), Some("read_log")),
+                (Some("push_b"), Some("push_c")),
+                (Some("read_log"), Some("push_c")),
+            ]
+        );
+        assert_eq!(ambiguities[0].conflicts, [std::any::type_name::<Log>()]);
+
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Error,
+        });
+        schedule.add_systems((push_a, push_b, read_log).in_set(TestSet::First).chain());
+        schedule.add_systems(push_c.ambiguous_with(TestSet::First));
+        schedule.build(&mut world).unwrap();
+        assert!(schedule.ambiguities().is_empty());
+    }
+
+    #[test]
+    fn dot() {
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.configure_sets(TestSet::Second.after(TestSet::First));
+        schedule.add_systems((
+            push_a.in_set(TestSet::First),
+            (push_b, push_c).chain().after(push_a),
+        ));
+        let dot = schedule.to_dot();
+        assert!(dot.starts_with("digraph schedule {\n    subgraph cluster_schedule {"));
+        assert!(dot.contains("label=\"TestSchedule\";"));
+        assert!(dot.contains("schedule_system_0 [label=\"komorebi_ecs::schedule::tests::push_a\""));
+        assert!(dot.contains("schedule_set_0 [label=\"Second\", style=dashed];"));
+        assert!(dot.contains("schedule_set_1 [label=\"First\", style=dashed];"));
+        assert!(
+            dot.contains("schedule_set_1 -> schedule_set_0;"),
+            "First runs before Second"
+        );
+        assert!(dot.contains("schedule_system_0 -> schedule_set_1 [style=dashed];"));
+        assert!(dot.contains("schedule_system_0 -> schedule_system_1;"));
+        assert!(dot.contains("schedule_system_0 -> schedule_system_2;"));
+        assert!(dot.contains("schedule_system_1 -> schedule_system_2;"));
+    }
+
+    #[test]
+    fn system_timings() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems((push_a, push_b));
+        schedule.run(&mut world);
+        world.init_resource::<SystemTimings>();
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        let mut timings = world.resource_mut::<SystemTimings>();
+        let mut names: Vec<_> = timings
+            .iter()
+            .map(|(name, _)| name.rsplit("::").next().unwrap().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["push_a", "push_b"]);
+        assert_eq!(timings.take().len(), 2);
+        assert_eq!(timings.iter().count(), 0);
+        drop(timings);
+
+        // Closures defined in the same function have the same name
+        let mut schedule = Schedule::new(TestSchedule);
+        for _ in 0..2 {
+            schedule.add_systems(|mut log: ResMut<Log>| log.0.push("closure"));
+        }
+        schedule.run(&mut world);
+        let timings = world.resource::<SystemTimings>();
+        let mut names: Vec<_> = timings
+            .iter()
+            .map(|(name, _)| name.rsplit("::").next().unwrap().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["{{closure}} #0", "{{closure}} #1"]);
+    }
+
+    #[test]
+    fn running_system_name() {
+        fn check_name(mut log: ResMut<Log>) {
+            let name = running_system().unwrap();
+            assert!(name.ends_with("::check_name"));
+            log.0.push("checked");
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule.add_systems(check_name);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Log>().0, ["checked"]);
+        assert!(running_system().is_none());
+    }
+
+    #[test]
+    fn missing_schedule() {
+        let mut world = World::new();
+        assert!(world.try_run_schedule(TestSchedule).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use std::any::type_name;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::{define_label, BoxedCondition, Condition, IntoSystem};
+
+define_label!(
+    /// A label for a group of systems
+    ///
+    /// Systems join a set with [`in_set`](crate::IntoSystemConfigs::in_set), and sets can be
+    /// ordered, nested and given run conditions with
+    /// [`Schedule::configure_sets`](crate::Schedule::configure_sets). A set with a single
+    /// system serves as a label for that system.
+    SystemSet
+);
+
+/// The set every system built from the function type `T` belongs to, so that functions can
+/// be used directly in [`before`](crate::IntoSystemConfigs::before) and
+/// [`after`](crate::IntoSystemConfigs::after)
+pub struct SystemTypeSet<T: 'static>(PhantomData<fn() -> T>);
+
+impl<T: 'static> SystemTypeSet<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: 'static> Default for SystemTypeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> fmt::Debug for SystemTypeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SystemTypeSet({})", type_name::<T>())
+    }
+}
+
+impl<T: 'static> Clone for SystemTypeSet<T> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> PartialEq for SystemTypeSet<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<T: 'static> Eq for SystemTypeSet<T> {}
+
+impl<T: 'static> Hash for SystemTypeSet<T> {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl<T: 'static> SystemSet for SystemTypeSet<T> {}
+
+/// Types that identify a [`SystemSet`]: sets themselves, and system functions
+pub trait IntoSystemSet<Marker>: Sized {
+    type Set: SystemSet;
+
+    fn into_system_set(self) -> Self::Set;
+}
+
+impl<S: SystemSet> IntoSystemSet<()> for S {
+    type Set = S;
+    fn into_system_set(self) -> S {
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct SystemTypeSetMarker;
+
+impl<Marker, F> IntoSystemSet<(SystemTypeSetMarker, Marker)> for F
+where
+    F: IntoSystem<(), (), Marker>,
+{
+    type Set = SystemTypeSet<F::System>;
+    fn into_system_set(self) -> Self::Set {
+        SystemTypeSet::new()
+    }
+}
+
+/// Ordering and membership of a system, or of a set
+#[derive(Clone, Default)]
+pub(crate) struct GraphInfo {
+    pub(crate) sets: Vec<Arc<dyn SystemSet>>,
+    pub(crate) before: Vec<Arc<dyn SystemSet>>,
+    pub(crate) after: Vec<Arc<dyn SystemSet>>,
+    pub(crate) ambiguous_with: Vec<Arc<dyn SystemSet>>,
+}
+
+impl GraphInfo {
+    pub(crate) fn extend(&mut self, other: GraphInfo) {
+        self.sets.extend(other.sets);
+        self.before.extend(other.before);
+        self.after.extend(other.after);
+        self.ambiguous_with.extend(other.ambiguous_with);
+    }
+}
+
+/// A [`SystemSet`] ready to be configured in a [`Schedule`](crate::Schedule)
+pub struct SystemSetConfig {
+    pub(crate) set: Arc<dyn SystemSet>,
+    pub(crate) graph_info: GraphInfo,
+    pub(crate) conditions: Vec<BoxedCondition>,
+}
+
+/// Types that can be turned into a [`SystemSetConfig`]
+pub trait IntoSystemSetConfig: Sized {
+    fn into_config(self) -> SystemSetConfig;
+
+    /// Nest this set inside `set`
+    fn in_set(self, set: impl SystemSet) -> SystemSetConfig {
+        let mut config = self.into_config();
+        config.graph_info.sets.push(Arc::new(set));
+        config
+    }
+
+    /// Run the systems of this set before those of `set`
+    fn before<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfig {
+        let mut config = self.into_config();
+        config
+            .graph_info
+            .before
+            .push(Arc::new(set.into_system_set()));
+        config
+    }
+
+    /// Run the systems of this set after those of `set`
+    fn after<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfig {
+        let mut config = self.into_config();
+        config
+            .graph_info
+            .after
+            .push(Arc::new(set.into_system_set()));
+        config
+    }
+
+    /// Only run the systems of this set when `condition` returns `true`
+    fn run_if<M>(self, condition: impl Condition<M>) -> SystemSetConfig {
+        let mut config = self.into_config();
+        config
+            .conditions
+            .push(Box::new(IntoSystem::into_system(condition)));
+        config
+    }
+
+    /// Do not report ambiguities between the systems of this set and those of `set`
+    fn ambiguous_with<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfig {
+        let mut config = self.into_config();
+        config
+            .graph_info
+            .ambiguous_with
+            .push(Arc::new(set.into_system_set()));
+        config
+    }
+}
+
+impl<S: SystemSet> IntoSystemSetConfig for S {
+    fn into_config(self) -> SystemSetConfig {
+        SystemSetConfig {
+            set: Arc::new(self),
+            graph_info: GraphInfo::default(),
+            conditions: Vec::new(),
+        }
+    }
+}
+
+impl IntoSystemSetConfig for SystemSetConfig {
+    fn into_config(self) -> SystemSetConfig {
+        self
+    }
+}
+
+/// One or more [`SystemSetConfig`]s
+pub trait IntoSystemSetConfigs<Marker> {
+    fn into_configs(self) -> Vec<SystemSetConfig>;
+}
+
+impl<C: IntoSystemSetConfig> IntoSystemSetConfigs<()> for C {
+    fn into_configs(self) -> Vec<SystemSetConfig> {
+        vec![self.into_config()]
+    }
+}
+
+macro_rules! impl_system_set_configs_tuple {
+    ($($set: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($set: IntoSystemSetConfig),*> IntoSystemSetConfigs<(SystemSetConfig,)>
+            for ($($set,)*)
+        {
+            fn into_configs(self) -> Vec<SystemSetConfig> {
+                let ($($set,)*) = self;
+                vec![$($set.into_config(),)*]
+            }
+        }
+    };
+}
+
+impl_system_set_configs_tuple!(A);
+impl_system_set_configs_tuple!(A, B);
+impl_system_set_configs_tuple!(A, B, C);
+impl_system_set_configs_tuple!(A, B, C, D);
+impl_system_set_configs_tuple!(A, B, C, D, E);
+impl_system_set_configs_tuple!(A, B, C, D, E, F);
+impl_system_set_configs_tuple!(A, B, C, D, E, F, G);
+impl_system_set_configs_tuple!(A, B, C, D, E, F, G, H);
@@ -2,7 +2,7 @@ use std::mem;
 
 use hibitset::{BitIter, BitSet, BitSetAnd, BitSetLike};
 
-use super::{Masked, Storage, StorageRefMut};
+use super::{Masked, Storage, StorageRef, StorageRefMut};
 
 #[doc(hidden)]
 pub trait Get<'a>: 'a {
@@ -81,6 +81,15 @@ impl<'a, 'b, S: Storage> Join<'a> for &'a mut StorageRefMut<'b, S> {
     }
 }
 
+impl<'a, 'b, S: Storage> Join<'a> for &'a StorageRef<'b, S> {
+    type Bits = &'a BitSet;
+    type Get = &'a S;
+    fn into_parts(self) -> (&'a BitSet, &'a S) {
+        let x: &'a Masked<S> = self;
+        (&x.mask, &x.inner)
+    }
+}
+
 pub struct JoinIter<'a, T: Join<'a>> {
     bits: BitIter<T::Bits>,
     get: T::Get,
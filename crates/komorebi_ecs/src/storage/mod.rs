@@ -14,6 +14,10 @@ use hibitset::{BitIter, BitSet, BitSetLike};
 
 pub trait AbstractStorage: Downcast + Send + 'static {
     fn free(&mut self, i: u32);
+    /// Number of stored components
+    fn component_count(&self) -> usize;
+    /// Type name of the storage
+    fn name(&self) -> &'static str;
 }
 impl_downcast!(AbstractStorage);
 
@@ -21,20 +25,41 @@ impl<S: Storage> AbstractStorage for Masked<S> {
     fn free(&mut self, i: u32) {
         self.remove(i);
     }
+
+    fn component_count(&self) -> usize {
+        self.len
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<S>()
+    }
 }
 
 pub trait Storage: Default + Send + 'static {
     type Component;
 
+    /// # Safety
+    ///
+    /// `i` must not hold a component.
     unsafe fn insert(&mut self, i: u32, x: Self::Component);
+    /// # Safety
+    ///
+    /// `i` must hold a component.
     unsafe fn remove(&mut self, i: u32) -> Self::Component;
+    /// # Safety
+    ///
+    /// `i` must hold a component.
     unsafe fn get(&self, i: u32) -> &Self::Component;
+    /// # Safety
+    ///
+    /// `i` must hold a component.
     unsafe fn get_mut(&mut self, i: u32) -> &mut Self::Component;
 }
 
 pub struct Masked<S: Storage> {
     inner: S,
     mask: BitSet,
+    len: usize,
 }
 
 impl<S: Storage> Masked<S> {
@@ -42,6 +67,7 @@ impl<S: Storage> Masked<S> {
         Self {
             inner: x,
             mask: BitSet::new(),
+            len: 0,
         }
     }
 
@@ -49,7 +75,10 @@ impl<S: Storage> Masked<S> {
         unsafe {
             let old = match self.mask.add(i) {
                 true => Some(self.inner.remove(i)),
-                false => None,
+                false => {
+                    self.len += 1;
+                    None
+                }
             };
             self.inner.insert(i, x);
             old
@@ -59,12 +88,43 @@ impl<S: Storage> Masked<S> {
     pub fn remove(&mut self, i: u32) -> Option<S::Component> {
         unsafe {
             match self.mask.remove(i) {
-                true => Some(self.inner.remove(i)),
+                true => {
+                    self.len -= 1;
+                    Some(self.inner.remove(i))
+                }
                 false => None,
             }
         }
     }
 
+    /// Number of stored components
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether index `i` holds a component
+    pub fn contains(&self, i: u32) -> bool {
+        self.mask.contains(i)
+    }
+
+    pub fn get(&self, i: u32) -> Option<&S::Component> {
+        match self.mask.contains(i) {
+            true => unsafe { Some(self.inner.get(i)) },
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, i: u32) -> Option<&mut S::Component> {
+        match self.mask.contains(i) {
+            true => unsafe { Some(self.inner.get_mut(i)) },
+            false => None,
+        }
+    }
+
     pub fn iter(&self) -> SingleIter<'_, S> {
         self.into_iter()
     }
@@ -111,6 +171,22 @@ impl<'a, S: Storage> DerefMut for StorageRefMut<'a, S> {
     }
 }
 
+/// Read-only access to a storage, as a system parameter
+pub struct StorageRef<'a, S>(StorageRefMut<'a, S>);
+
+impl<'a, S> StorageRef<'a, S> {
+    pub fn new(inner: StorageRefMut<'a, S>) -> Self {
+        Self(inner)
+    }
+}
+
+impl<'a, S: Storage> Deref for StorageRef<'a, S> {
+    type Target = Masked<S>;
+    fn deref(&self) -> &Masked<S> {
+        &self.0
+    }
+}
+
 impl<'a, S: Storage> IntoIterator for &'a Masked<S> {
     type Item = &'a S::Component;
     type IntoIter = SingleIter<'a, S>;
@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::{Access, In, IntoSystem, System, SystemMeta, SystemSet, SystemTypeSet, World};
+
+/// A [`System`] made from a function taking `&mut World`
+///
+/// Exclusive systems never run alongside other systems, so they are the place for work that
+/// restructures the world, such as spawning entities or registering storages.
+pub struct ExclusiveFunctionSystem<Marker, F> {
+    func: F,
+    meta: SystemMeta,
+    marker: PhantomData<fn() -> Marker>,
+}
+
+#[doc(hidden)]
+pub struct IsExclusiveFunctionSystem;
+
+impl<Marker: 'static, F: ExclusiveSystemParamFunction<Marker>>
+    IntoSystem<F::In, F::Out, (IsExclusiveFunctionSystem, Marker)> for F
+{
+    type System = ExclusiveFunctionSystem<Marker, F>;
+    fn into_system(func: Self) -> Self::System {
+        let mut meta = SystemMeta::new::<F>();
+        meta.access = Access::world();
+        ExclusiveFunctionSystem {
+            func,
+            meta,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Marker: 'static, F: ExclusiveSystemParamFunction<Marker>> System
+    for ExclusiveFunctionSystem<Marker, F>
+{
+    type In = F::In;
+    type Out = F::Out;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.meta.name.clone()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        true
+    }
+
+    fn access(&self) -> &Access {
+        &self.meta.access
+    }
+
+    fn default_system_sets(&self) -> Vec<Arc<dyn SystemSet>> {
+        vec![Arc::new(SystemTypeSet::<Self>::new())]
+    }
+
+    fn initialize(&mut self, _world: &mut World) {}
+
+    fn run_shared(&mut self, _input: Self::In, _world: &World) -> Self::Out {
+        panic!(
+            "system {} is exclusive and cannot run with shared world access",
+            self.meta.name
+        );
+    }
+
+    fn run(&mut self, input: Self::In, world: &mut World) -> Self::Out {
+        self.func.run(input, world)
+    }
+}
+
+/// Functions that can be turned into an [`ExclusiveFunctionSystem`]
+pub trait ExclusiveSystemParamFunction<Marker>: Send + 'static {
+    type In;
+    type Out;
+
+    fn run(&mut self, input: Self::In, world: &mut World) -> Self::Out;
+}
+
+impl<Out, Func> ExclusiveSystemParamFunction<fn(&mut World) -> Out> for Func
+where
+    Func: FnMut(&mut World) -> Out + Send + 'static,
+    Out: 'static,
+{
+    type In = ();
+    type Out = Out;
+
+    fn run(&mut self, _input: (), world: &mut World) -> Out {
+        self(world)
+    }
+}
+
+impl<Input, Out, Func> ExclusiveSystemParamFunction<fn(In<Input>, &mut World) -> Out> for Func
+where
+    Func: FnMut(In<Input>, &mut World) -> Out + Send + 'static,
+    Out: 'static,
+{
+    type In = Input;
+    type Out = Out;
+
+    fn run(&mut self, input: Input, world: &mut World) -> Out {
+        self(In(input), world)
+    }
+}
@@ -0,0 +1,150 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::{
+    Access, In, IntoSystem, System, SystemMeta, SystemParam, SystemParamItem, SystemSet,
+    SystemTypeSet, World,
+};
+
+/// A [`System`] made from a function whose arguments are all [`SystemParam`]s
+pub struct FunctionSystem<Marker, F: SystemParamFunction<Marker>> {
+    func: F,
+    param_state: Option<<F::Param as SystemParam>::State>,
+    meta: SystemMeta,
+    marker: PhantomData<fn() -> Marker>,
+}
+
+#[doc(hidden)]
+pub struct IsFunctionSystem;
+
+impl<Marker: 'static, F: SystemParamFunction<Marker>>
+    IntoSystem<F::In, F::Out, (IsFunctionSystem, Marker)> for F
+{
+    type System = FunctionSystem<Marker, F>;
+    fn into_system(func: Self) -> Self::System {
+        FunctionSystem {
+            func,
+            param_state: None,
+            meta: SystemMeta::new::<F>(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Marker: 'static, F: SystemParamFunction<Marker>> System for FunctionSystem<Marker, F> {
+    type In = F::In;
+    type Out = F::Out;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.meta.name.clone()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+
+    fn access(&self) -> &Access {
+        &self.meta.access
+    }
+
+    fn default_system_sets(&self) -> Vec<Arc<dyn SystemSet>> {
+        vec![Arc::new(SystemTypeSet::<Self>::new())]
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        if self.param_state.is_none() {
+            self.param_state = Some(F::Param::init_state(world, &mut self.meta));
+        }
+    }
+
+    fn run_shared(&mut self, input: Self::In, world: &World) -> Self::Out {
+        let state = self
+            .param_state
+            .as_mut()
+            .unwrap_or_else(|| panic!("system {} was not initialized", self.meta.name));
+        let params = F::Param::get_param(state, &self.meta, world);
+        self.func.run(input, params)
+    }
+}
+
+/// Functions that can be turned into a [`FunctionSystem`]
+pub trait SystemParamFunction<Marker>: Send + 'static {
+    type In;
+    type Out;
+    type Param: SystemParam;
+
+    fn run(&mut self, input: Self::In, param_value: SystemParamItem<Self::Param>) -> Self::Out;
+}
+
+macro_rules! impl_system_function {
+    ($($param: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<Out, Func: Send + 'static, $($param: SystemParam),*>
+            SystemParamFunction<fn($($param,)*) -> Out> for Func
+        where
+            for<'a> &'a mut Func:
+                FnMut($($param),*) -> Out + FnMut($(SystemParamItem<$param>),*) -> Out,
+            Out: 'static,
+        {
+            type In = ();
+            type Out = Out;
+            type Param = ($($param,)*);
+
+            fn run(&mut self, _input: (), param_value: SystemParamItem<($($param,)*)>) -> Out {
+                // Calling through a generic function makes the compiler pick the
+                // `FnMut(SystemParamItem<..>)` bound instead of `FnMut($param)`
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<Out, $($param,)*>(
+                    mut f: impl FnMut($($param,)*) -> Out,
+                    $($param: $param,)*
+                ) -> Out {
+                    f($($param,)*)
+                }
+                let ($($param,)*) = param_value;
+                call_inner(self, $($param),*)
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<Input, Out, Func: Send + 'static, $($param: SystemParam),*>
+            SystemParamFunction<fn(In<Input>, $($param,)*) -> Out> for Func
+        where
+            for<'a> &'a mut Func:
+                FnMut(In<Input>, $($param),*) -> Out
+                + FnMut(In<Input>, $(SystemParamItem<$param>),*) -> Out,
+            Out: 'static,
+        {
+            type In = Input;
+            type Out = Out;
+            type Param = ($($param,)*);
+
+            fn run(&mut self, input: Input, param_value: SystemParamItem<($($param,)*)>) -> Out {
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<Input, Out, $($param,)*>(
+                    mut f: impl FnMut(In<Input>, $($param,)*) -> Out,
+                    input: In<Input>,
+                    $($param: $param,)*
+                ) -> Out {
+                    f(input, $($param,)*)
+                }
+                let ($($param,)*) = param_value;
+                call_inner(self, In(input), $($param),*)
+            }
+        }
+    };
+}
+
+impl_system_function!();
+impl_system_function!(A);
+impl_system_function!(A, B);
+impl_system_function!(A, B, C);
+impl_system_function!(A, B, C, D);
+impl_system_function!(A, B, C, D, E);
+impl_system_function!(A, B, C, D, E, F);
+impl_system_function!(A, B, C, D, E, F, G);
+impl_system_function!(A, B, C, D, E, F, G, H);
+impl_system_function!(A, B, C, D, E, F, G, H, I);
+impl_system_function!(A, B, C, D, E, F, G, H, I, J);
+impl_system_function!(A, B, C, D, E, F, G, H, I, J, K);
+impl_system_function!(A, B, C, D, E, F, G, H, I, J, K, L);
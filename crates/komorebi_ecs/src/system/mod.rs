@@ -0,0 +1,201 @@
+mod exclusive_function_system;
+mod function_system;
+mod param;
+mod pipe;
+pub mod system_adapter;
+
+pub use exclusive_function_system::*;
+pub use function_system::*;
+pub use param::*;
+pub use pipe::*;
+
+use std::any::type_name;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::{Access, SystemSet, World};
+
+/// Metadata of a system, shared with its parameters while they are initialized and fetched
+pub struct SystemMeta {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) access: Access,
+}
+
+impl SystemMeta {
+    pub(crate) fn new<T>() -> Self {
+        Self {
+            name: type_name::<T>().into(),
+            access: Access::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The access of the system, which parameters extend in [`SystemParam::init_state`]
+    pub fn access_mut(&mut self) -> &mut Access {
+        &mut self.access
+    }
+}
+
+/// A unit of logic that is run against a [`World`] by a [`Schedule`](crate::Schedule)
+pub trait System: Send + 'static {
+    /// The input piped into the system, `()` for regular systems
+    type In;
+    /// The output of the system
+    type Out;
+
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Whether the system needs `&mut World`, in which case it always runs alone
+    fn is_exclusive(&self) -> bool;
+
+    /// The resources and storages the system uses, known once it is initialized
+    fn access(&self) -> &Access;
+
+    /// Sets the system belongs to without being configured, such as the set of its function
+    fn default_system_sets(&self) -> Vec<Arc<dyn SystemSet>> {
+        Vec::new()
+    }
+
+    /// Prepare the state of the system parameters; must be called before the first run
+    fn initialize(&mut self, world: &mut World);
+
+    /// Run the system with shared access to the world
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is exclusive.
+    fn run_shared(&mut self, input: Self::In, world: &World) -> Self::Out;
+
+    /// Run the system with unique access to the world
+    fn run(&mut self, input: Self::In, world: &mut World) -> Self::Out {
+        self.run_shared(input, world)
+    }
+}
+
+pub type BoxedSystem<In = (), Out = ()> = Box<dyn System<In = In, Out = Out>>;
+
+/// Conversion of functions (and systems themselves) into a [`System`]
+pub trait IntoSystem<In, Out, Marker>: Sized {
+    type System: System<In = In, Out = Out>;
+
+    fn into_system(this: Self) -> Self::System;
+
+    /// Pass the output of this system into the input of `system`
+    fn pipe<B, Final, MarkerB>(self, system: B) -> PipeSystem<Self::System, B::System>
+    where
+        B: IntoSystem<Out, Final, MarkerB>,
+    {
+        PipeSystem::new(
+            IntoSystem::into_system(self),
+            IntoSystem::into_system(system),
+        )
+    }
+}
+
+impl<T: System> IntoSystem<T::In, T::Out, ()> for T {
+    type System = T;
+    fn into_system(this: Self) -> T {
+        this
+    }
+}
+
+/// Wrapper for the value piped into a system, which must be its first parameter
+pub struct In<T>(pub T);
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[derive(Default)]
+    struct Counter(u32);
+
+    fn increment(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    #[test]
+    fn function_system() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut system = IntoSystem::into_system(increment);
+        system.initialize(&mut world);
+        assert!(!system.is_exclusive());
+        system.run((), &mut world);
+        system.run((), &mut world);
+        assert_eq!(world.resource::<Counter>().0, 2);
+    }
+
+    #[test]
+    fn local_state() {
+        fn count(mut local: Local<u32>, mut counter: ResMut<Counter>) {
+            *local += 1;
+            counter.0 = *local;
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut system = IntoSystem::into_system(count);
+        system.initialize(&mut world);
+        for _ in 0..3 {
+            system.run((), &mut world);
+        }
+        assert_eq!(world.resource::<Counter>().0, 3);
+    }
+
+    #[test]
+    fn exclusive_system() {
+        fn spawn(world: &mut World) -> Entity {
+            world.spawn()
+        }
+
+        let mut world = World::new();
+        let mut system = IntoSystem::into_system(spawn);
+        system.initialize(&mut world);
+        assert!(system.is_exclusive());
+        let entity = system.run((), &mut world);
+        assert!(world.contains(entity));
+    }
+
+    #[test]
+    #[should_panic(expected = "exclusive")]
+    fn exclusive_system_needs_unique_world() {
+        let mut world = World::new();
+        let mut system = IntoSystem::into_system(|_world: &mut World| {});
+        system.initialize(&mut world);
+        system.run_shared((), &world);
+    }
+
+    #[test]
+    fn pipe() {
+        fn parse(counter: Res<Counter>) -> Result<u32, String> {
+            match counter.0 {
+                0 => Err("empty".to_string()),
+                n => Ok(n * 10),
+            }
+        }
+
+        fn store(In(result): In<Result<u32, String>>, world: &mut World) -> Option<String> {
+            match result {
+                Ok(n) => {
+                    world.resource_mut::<Counter>().0 = n;
+                    None
+                }
+                Err(e) => Some(e),
+            }
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut system = parse.pipe(store);
+        system.initialize(&mut world);
+        assert!(system.is_exclusive());
+        assert_eq!(system.run((), &mut world), Some("empty".to_string()));
+
+        world.resource_mut::<Counter>().0 = 2;
+        assert_eq!(system.run((), &mut world), None);
+        assert_eq!(world.resource::<Counter>().0, 20);
+    }
+}
@@ -0,0 +1,188 @@
+use std::any::type_name;
+use std::ops::{Deref, DerefMut};
+
+use crate::{Fetch, Res, ResMut, Resource, Storage, StorageRef, StorageRefMut, SystemMeta, World};
+
+/// A parameter of a [`FunctionSystem`](crate::FunctionSystem), fetched from the [`World`]
+/// each time the system runs
+pub trait SystemParam: Sized {
+    /// Data kept by the system between runs
+    type State: Send + 'static;
+    /// The type handed to the system function
+    type Item<'w, 's>;
+
+    fn init_state(world: &mut World, meta: &mut SystemMeta) -> Self::State;
+
+    fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        meta: &SystemMeta,
+        world: &'w World,
+    ) -> Self::Item<'w, 's>;
+}
+
+/// The item a [`SystemParam`] yields for the given lifetimes
+pub type SystemParamItem<'w, 's, P> = <P as SystemParam>::Item<'w, 's>;
+
+impl<'a, R: Resource> SystemParam for Res<'a, R> {
+    type State = ();
+    type Item<'w, 's> = Res<'w, R>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) {
+        meta.access.add_read_resource::<R>();
+    }
+
+    fn get_param<'w>(_state: &mut (), meta: &SystemMeta, world: &'w World) -> Res<'w, R> {
+        world.get_resource().unwrap_or_else(|| {
+            panic!(
+                "resource {} requested by system {} does not exist",
+                type_name::<R>(),
+                meta.name
+            )
+        })
+    }
+}
+
+impl<'a, R: Resource> SystemParam for ResMut<'a, R> {
+    type State = ();
+    type Item<'w, 's> = ResMut<'w, R>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) {
+        meta.access.add_write_resource::<R>();
+    }
+
+    fn get_param<'w>(_state: &mut (), meta: &SystemMeta, world: &'w World) -> ResMut<'w, R> {
+        world.get_resource_mut().unwrap_or_else(|| {
+            panic!(
+                "resource {} requested by system {} does not exist",
+                type_name::<R>(),
+                meta.name
+            )
+        })
+    }
+}
+
+impl<'a, R: Resource> SystemParam for Option<Res<'a, R>> {
+    type State = ();
+    type Item<'w, 's> = Option<Res<'w, R>>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) {
+        meta.access.add_read_resource::<R>();
+    }
+
+    fn get_param<'w>(_state: &mut (), _meta: &SystemMeta, world: &'w World) -> Option<Res<'w, R>> {
+        world.get_resource()
+    }
+}
+
+impl<'a, R: Resource> SystemParam for Option<ResMut<'a, R>> {
+    type State = ();
+    type Item<'w, 's> = Option<ResMut<'w, R>>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) {
+        meta.access.add_write_resource::<R>();
+    }
+
+    fn get_param<'w>(
+        _state: &mut (),
+        _meta: &SystemMeta,
+        world: &'w World,
+    ) -> Option<ResMut<'w, R>> {
+        world.get_resource_mut()
+    }
+}
+
+impl<'a, S: Storage> SystemParam for StorageRefMut<'a, S> {
+    type State = ();
+    type Item<'w, 's> = StorageRefMut<'w, S>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) {
+        meta.access.add_write_storage::<S>();
+    }
+
+    fn get_param<'w>(
+        _state: &mut (),
+        _meta: &SystemMeta,
+        world: &'w World,
+    ) -> StorageRefMut<'w, S> {
+        S::fetch(world)
+    }
+}
+
+impl<'a, S: Storage> SystemParam for StorageRef<'a, S> {
+    type State = ();
+    type Item<'w, 's> = StorageRef<'w, S>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) {
+        meta.access.add_read_storage::<S>();
+    }
+
+    fn get_param<'w>(_state: &mut (), _meta: &SystemMeta, world: &'w World) -> StorageRef<'w, S> {
+        StorageRef::new(S::fetch(world))
+    }
+}
+
+/// A value private to a single system, kept between its runs
+pub struct Local<'s, T>(&'s mut T);
+
+impl<'s, T> Deref for Local<'s, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.0
+    }
+}
+
+impl<'s, T> DerefMut for Local<'s, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.0
+    }
+}
+
+impl<'a, T: Default + Send + 'static> SystemParam for Local<'a, T> {
+    type State = T;
+    type Item<'w, 's> = Local<'s, T>;
+
+    fn init_state(_world: &mut World, _meta: &mut SystemMeta) -> T {
+        T::default()
+    }
+
+    fn get_param<'s>(state: &'s mut T, _meta: &SystemMeta, _world: &World) -> Local<'s, T> {
+        Local(state)
+    }
+}
+
+macro_rules! impl_system_param_tuple {
+    ($($param: ident),*) => {
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        impl<$($param: SystemParam),*> SystemParam for ($($param,)*) {
+            type State = ($($param::State,)*);
+            type Item<'w, 's> = ($($param::Item<'w, 's>,)*);
+
+            fn init_state(world: &mut World, meta: &mut SystemMeta) -> Self::State {
+                ($($param::init_state(world, meta),)*)
+            }
+
+            fn get_param<'w, 's>(
+                state: &'s mut Self::State,
+                meta: &SystemMeta,
+                world: &'w World,
+            ) -> Self::Item<'w, 's> {
+                let ($($param,)*) = state;
+                ($($param::get_param($param, meta, world),)*)
+            }
+        }
+    };
+}
+
+impl_system_param_tuple!();
+impl_system_param_tuple!(A);
+impl_system_param_tuple!(A, B);
+impl_system_param_tuple!(A, B, C);
+impl_system_param_tuple!(A, B, C, D);
+impl_system_param_tuple!(A, B, C, D, E);
+impl_system_param_tuple!(A, B, C, D, E, F);
+impl_system_param_tuple!(A, B, C, D, E, F, G);
+impl_system_param_tuple!(A, B, C, D, E, F, G, H);
+impl_system_param_tuple!(A, B, C, D, E, F, G, H, I);
+impl_system_param_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_system_param_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_system_param_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
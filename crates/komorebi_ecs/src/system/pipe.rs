@@ -0,0 +1,67 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::{Access, System, SystemSet, World};
+
+/// A [`System`] that feeds the output of `A` into the input of `B`
+///
+/// Created with [`IntoSystem::pipe`](crate::IntoSystem::pipe). The pipe is exclusive if
+/// either side is.
+pub struct PipeSystem<A, B> {
+    a: A,
+    b: B,
+    name: Cow<'static, str>,
+    access: Access,
+}
+
+impl<A: System, B: System<In = A::Out>> PipeSystem<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        let name = format!("Pipe({}, {})", a.name(), b.name()).into();
+        Self {
+            a,
+            b,
+            name,
+            access: Access::new(),
+        }
+    }
+}
+
+impl<A: System, B: System<In = A::Out>> System for PipeSystem<A, B> {
+    type In = A::In;
+    type Out = B::Out;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        self.a.is_exclusive() || self.b.is_exclusive()
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn default_system_sets(&self) -> Vec<Arc<dyn SystemSet>> {
+        let mut sets = self.a.default_system_sets();
+        sets.extend(self.b.default_system_sets());
+        sets
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.a.initialize(world);
+        self.b.initialize(world);
+        self.access = self.a.access().clone();
+        self.access.extend(self.b.access());
+    }
+
+    fn run_shared(&mut self, input: Self::In, world: &World) -> Self::Out {
+        let value = self.a.run_shared(input, world);
+        self.b.run_shared(value, world)
+    }
+
+    fn run(&mut self, input: Self::In, world: &mut World) -> Self::Out {
+        let value = self.a.run(input, world);
+        self.b.run(value, world)
+    }
+}
@@ -0,0 +1,39 @@
+//! Systems meant to be the second half of a [pipe](crate::IntoSystem::pipe), typically
+//! consuming the `Result` returned by a fallible system.
+//!
+//! ```ignore
+//! app.add_systems(Update, load_level.pipe(system_adapter::warn));
+//! ```
+
+use std::fmt::{Debug, Display};
+
+use komorebi_utils::tracing::{error as log_error, info as log_info, warn as log_warn};
+
+use crate::In;
+
+/// Unwraps the piped `Result`, panicking on `Err`
+pub fn unwrap<T, E: Debug>(In(result): In<Result<T, E>>) -> T {
+    result.unwrap()
+}
+
+/// Discards the piped value
+pub fn ignore<T>(In(_): In<T>) {}
+
+/// Logs the piped value at info level
+pub fn info<T: Debug>(In(value): In<T>) {
+    log_info!("{:?}", value);
+}
+
+/// Logs the error of the piped `Result` at warn level
+pub fn warn<E: Display>(In(result): In<Result<(), E>>) {
+    if let Err(e) = result {
+        log_warn!("{}", e);
+    }
+}
+
+/// Logs the error of the piped `Result` at error level
+pub fn error<E: Display>(In(result): In<Result<(), E>>) {
+    if let Err(e) = result {
+        log_error!("{}", e);
+    }
+}
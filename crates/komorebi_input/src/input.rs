@@ -0,0 +1,90 @@
+use std::{collections::HashSet, hash::Hash};
+
+use serde::{Deserialize, Serialize};
+
+/// Whether a button is pressed or released
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ButtonState {
+    Pressed,
+    Released,
+}
+
+impl ButtonState {
+    pub fn is_pressed(&self) -> bool {
+        *self == ButtonState::Pressed
+    }
+}
+
+/// Resource with the state of buttons of type `T`, like [`KeyCode`](crate::KeyCode)s
+///
+/// The `just_` states last for the update in which the button changed.
+#[derive(Clone, Debug)]
+pub struct Input<T: Copy + Eq + Hash> {
+    pressed: HashSet<T>,
+    just_pressed: HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> Default for Input<T> {
+    fn default() -> Self {
+        Self {
+            pressed: HashSet::new(),
+            just_pressed: HashSet::new(),
+            just_released: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Input<T> {
+    pub fn press(&mut self, button: T) {
+        if self.pressed.insert(button) {
+            self.just_pressed.insert(button);
+        }
+    }
+
+    pub fn release(&mut self, button: T) {
+        if self.pressed.remove(&button) {
+            self.just_released.insert(button);
+        }
+    }
+
+    pub fn pressed(&self, button: T) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    pub fn any_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|button| self.pressed(button))
+    }
+
+    pub fn just_pressed(&self, button: T) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    pub fn just_released(&self, button: T) -> bool {
+        self.just_released.contains(&button)
+    }
+
+    pub fn get_pressed(&self) -> impl Iterator<Item = &T> {
+        self.pressed.iter()
+    }
+
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &T> {
+        self.just_pressed.iter()
+    }
+
+    pub fn get_just_released(&self) -> impl Iterator<Item = &T> {
+        self.just_released.iter()
+    }
+
+    /// Forget the `just_` states, at the start of every update
+    pub fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+
+    /// Release every button, without marking them as just released
+    pub fn reset_all(&mut self) {
+        self.pressed.clear();
+        self.clear();
+    }
+}
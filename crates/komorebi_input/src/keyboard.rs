@@ -0,0 +1,141 @@
+use komorebi_ecs::{EventReader, ResMut};
+use serde::{Deserialize, Serialize};
+
+use crate::{ButtonState, Input};
+
+/// A key, named after its position on a US QWERTY layout
+///
+/// Keys without a variant are still reported by their [`KeyboardInput::scan_code`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum KeyCode {
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    Key0,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Escape,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Insert,
+    Home,
+    Delete,
+    End,
+    PageDown,
+    PageUp,
+    Left,
+    Up,
+    Right,
+    Down,
+    /// Backspace
+    Back,
+    /// Enter
+    Return,
+    Space,
+    Tab,
+    Minus,
+    Equals,
+    Comma,
+    Period,
+    Slash,
+    Semicolon,
+    Apostrophe,
+    /// The backtick key
+    Grave,
+    LBracket,
+    RBracket,
+    Backslash,
+    LAlt,
+    RAlt,
+    LControl,
+    RControl,
+    LShift,
+    RShift,
+    /// The Windows or Command key
+    LWin,
+    /// The Windows or Command key
+    RWin,
+}
+
+/// Event sent when a key is pressed or released
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardInput {
+    /// Platform-specific code of the physical key
+    pub scan_code: u32,
+    pub key_code: Option<KeyCode>,
+    pub state: ButtonState,
+}
+
+impl KeyboardInput {
+    pub fn pressed(key_code: KeyCode) -> Self {
+        Self {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state: ButtonState::Pressed,
+        }
+    }
+
+    pub fn released(key_code: KeyCode) -> Self {
+        Self {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state: ButtonState::Released,
+        }
+    }
+}
+
+/// Updates [`Input<KeyCode>`] from the [`KeyboardInput`] events
+pub fn keyboard_input_system(
+    mut input: ResMut<Input<KeyCode>>,
+    mut events: EventReader<KeyboardInput>,
+) {
+    input.clear();
+    for event in events.read() {
+        if let Some(key_code) = event.key_code {
+            match event.state {
+                ButtonState::Pressed => input.press(key_code),
+                ButtonState::Released => input.release(key_code),
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+mod input;
+mod keyboard;
+mod mouse;
+mod recording;
+
+pub use input::*;
+pub use keyboard::*;
+pub use mouse::*;
+pub use recording::*;
+
+use komorebi_app::prelude::*;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{
+        ButtonState, Input, InputPlugin, InputRecordPlugin, InputReplayPlugin, KeyCode,
+        MouseButton, WorldChecksum,
+    };
+}
+
+/// Adds the input events, and the [`Input`] resources they update in [`PreUpdate`]
+///
+/// The windowing backend sends the events. Tests can send them by hand with
+/// [`App::send_event`].
+#[derive(Default)]
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<KeyboardInput>()
+            .add_event::<MouseButtonInput>()
+            .add_event::<CursorMoved>()
+            .add_event::<MouseWheel>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Input<MouseButton>>()
+            .add_systems(
+                PreUpdate,
+                (keyboard_input_system, mouse_button_input_system),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn injected_input() {
+        let mut app = App::new();
+        app.add_plugins(InputPlugin)
+            .send_event(KeyboardInput::pressed(KeyCode::Space))
+            .send_event(MouseButtonInput {
+                button: MouseButton::Left,
+                state: ButtonState::Pressed,
+            })
+            .step_n(1);
+        let keys = app.world.resource::<Input<KeyCode>>();
+        assert!(keys.pressed(KeyCode::Space) && keys.just_pressed(KeyCode::Space));
+        assert!(app
+            .world
+            .resource::<Input<MouseButton>>()
+            .just_pressed(MouseButton::Left));
+        drop(keys);
+
+        app.step_n(1);
+        let keys = app.world.resource::<Input<KeyCode>>();
+        assert!(keys.pressed(KeyCode::Space) && !keys.just_pressed(KeyCode::Space));
+        drop(keys);
+
+        app.send_event(KeyboardInput::released(KeyCode::Space))
+            .step_n(1);
+        let keys = app.world.resource::<Input<KeyCode>>();
+        assert!(!keys.pressed(KeyCode::Space) && keys.just_released(KeyCode::Space));
+    }
+}
@@ -0,0 +1,55 @@
+use komorebi_ecs::{EventReader, ResMut};
+use serde::{Deserialize, Serialize};
+
+use crate::{ButtonState, Input};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other(u16),
+}
+
+/// Event sent when a mouse button is pressed or released
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MouseButtonInput {
+    pub button: MouseButton,
+    pub state: ButtonState,
+}
+
+/// Event sent when the cursor moves over the window
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CursorMoved {
+    /// Physical pixels from the top left corner of the window
+    pub position: [f32; 2],
+}
+
+/// How far a [`MouseWheel`] event scrolled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouseScrollUnit {
+    Line,
+    Pixel,
+}
+
+/// Event sent when the mouse wheel or touchpad scrolls
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MouseWheel {
+    pub unit: MouseScrollUnit,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Updates [`Input<MouseButton>`] from the [`MouseButtonInput`] events
+pub fn mouse_button_input_system(
+    mut input: ResMut<Input<MouseButton>>,
+    mut events: EventReader<MouseButtonInput>,
+) {
+    input.clear();
+    for event in events.read() {
+        match event.state {
+            ButtonState::Pressed => input.press(event.button),
+            ButtonState::Released => input.release(event.button),
+        }
+    }
+}
@@ -0,0 +1,794 @@
+use std::{
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use komorebi_app::{prelude::*, BoxedError, MainScheduleOrder, PluginDependency};
+use komorebi_core::{time_system, Time, TimePlugin, TimeUpdateStrategy};
+use komorebi_ecs::{
+    EventReader, Events, IntoSystemConfigs, ManualEventReader, Res, ResMut, ScheduleLabel, Storage,
+    World,
+};
+use komorebi_utils::tracing::{error, info, warn};
+use komorebi_window::{WindowCloseRequested, WindowFocused, WindowResized};
+use serde::{Deserialize, Serialize};
+
+use crate::{CursorMoved, InputPlugin, KeyboardInput, MouseButtonInput, MouseWheel};
+
+/// An input or window event sent during a [`RecordedFrame`]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    Keyboard(KeyboardInput),
+    MouseButton(MouseButtonInput),
+    CursorMoved(CursorMoved),
+    MouseWheel(MouseWheel),
+    WindowResized(WindowResized),
+    WindowFocused(WindowFocused),
+    WindowCloseRequested(WindowCloseRequested),
+}
+
+/// The input and window events of one update, with the real time elapsed since the previous update
+///
+/// Events of the same type keep their order. Events of different types are independent, so
+/// they are grouped by type.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub delta: Duration,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// Input and window events of a session, written by [`InputRecordPlugin`] and read by [`InputReplayPlugin`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub engine_version: String,
+    pub frames: Vec<RecordedFrame>,
+    /// [`WorldChecksum`] of the world after the last frame
+    pub checksum: Option<u64>,
+}
+
+impl Default for InputRecording {
+    fn default() -> Self {
+        Self {
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            frames: Vec::new(),
+            checksum: None,
+        }
+    }
+}
+
+impl InputRecording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a recording from a RON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| RecordingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        ron::from_str(&text).map_err(|e| RecordingError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Write the recording to a RON file, on a single line
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordingError> {
+        let path = path.as_ref();
+        let text = ron::to_string(self).map_err(|e| RecordingError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        std::fs::write(path, text).map_err(|source| RecordingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Error returned when reading or writing an [`InputRecording`]
+#[derive(Debug)]
+pub enum RecordingError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// The file is not a valid recording
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io { path, source } => {
+                write!(f, "cannot access recording {}: {source}", path.display())
+            }
+            RecordingError::Parse { path, message } => {
+                write!(f, "invalid recording {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for RecordingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RecordingError::Io { source, .. } => Some(source),
+            RecordingError::Parse { .. } => None,
+        }
+    }
+}
+
+/// Resource listing what the checksum of a recording covers
+///
+/// The checksum always hashes the entity count, and the frame count and elapsed virtual time of
+/// [`Time`]. Storages whose components are `Hash` can be added under a name with
+/// [`WorldChecksum::with_storage`], and anything else with [`WorldChecksum::with_fn`]. The
+/// names, rather than type names, identify what is hashed, and [`ChecksumHasher`] always uses
+/// the same algorithm, so checksums only change with the names and the hashed data.
+///
+/// Counts alone miss most divergences, so the recording plugins warn when nothing else is
+/// hashed.
+#[derive(Clone, Default)]
+pub struct WorldChecksum {
+    hashers: Vec<(&'static str, HashFn)>,
+}
+
+/// Adds part of the world to a [`WorldChecksum`]
+pub type HashFn = fn(&World, &mut ChecksumHasher);
+
+impl WorldChecksum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also hash the components of `S` under `name`, in entity order
+    pub fn with_storage<S: Storage>(self, name: &'static str) -> Self
+    where
+        S::Component: Hash,
+    {
+        self.with_fn(name, |world, hasher| {
+            if !world.is_registered::<S>() {
+                hasher.write_u8(0);
+                return;
+            }
+            hasher.write_u8(1);
+            let mut count = 0;
+            for component in world.get::<S>().iter() {
+                component.hash(hasher);
+                count += 1;
+            }
+            hasher.write_usize(count);
+        })
+    }
+
+    /// Also hash what `hasher` adds, under `name`
+    pub fn with_fn(mut self, name: &'static str, hasher: HashFn) -> Self {
+        self.hashers.push((name, hasher));
+        self
+    }
+
+    /// Whether only the counts and [`Time`] are hashed
+    pub fn hashes_only_counts(&self) -> bool {
+        self.hashers.is_empty()
+    }
+
+    /// Warns when the checksum set up for `plugin` would only hash counts
+    fn warn_if_only_counts(app: &App, plugin: &str) {
+        if app.world.resource::<WorldChecksum>().hashes_only_counts() {
+            warn!(
+                "the checksum of {plugin} only hashes the entity count and time, insert a \
+                 `WorldChecksum` resource hashing the storages the game state lives in"
+            );
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if a storage is borrowed.
+    pub fn checksum(&self, world: &World) -> u64 {
+        let mut hasher = ChecksumHasher::default();
+        world.entity_count().hash(&mut hasher);
+        if let Some(time) = world.get_resource::<Time>() {
+            time.frame_count().hash(&mut hasher);
+            time.elapsed().hash(&mut hasher);
+        }
+        for (name, hash) in &self.hashers {
+            name.hash(&mut hasher);
+            hash(world, &mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// The 64-bit FNV-1a hasher of [`WorldChecksum`]
+///
+/// Integers are hashed as little-endian bytes, `usize` and `isize` as 64-bit ones, so the
+/// checksum of the same data is the same on every platform and with every compiler.
+#[derive(Clone, Copy, Debug)]
+pub struct ChecksumHasher(u64);
+
+impl Default for ChecksumHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for ChecksumHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// Records the input and window events of every update, with its real delta, to an
+/// [`InputRecording`]
+///
+/// The windowing backend converts the winit `WindowEvent`s to the input events, from which the
+/// [`Input`](crate::Input) resources are updated, and to the window events, such as resizes,
+/// focus changes and close requests, so the events are all a replay needs. The file is written
+/// with the [`WorldChecksum`] of the world in [`RecordingChecksum`] of the update in which an
+/// [`AppExit`] is sent. If the app stops without one, like on a panic, the frames recorded so
+/// far are written without checksum when the [`InputRecorder`] is dropped.
+#[derive(Clone, Debug)]
+pub struct InputRecordPlugin {
+    pub path: PathBuf,
+}
+
+impl InputRecordPlugin {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// Schedule run right after [`Last`] on every update, in which the recording plugins compute
+/// the [`WorldChecksum`] once every other system of the update ran
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecordingChecksum;
+impl ScheduleLabel for RecordingChecksum {}
+
+impl RecordingChecksum {
+    /// Runs the schedule after [`Last`], unless it already is
+    fn add_to_main_order(app: &mut App) {
+        let mut order = app.world.resource_mut::<MainScheduleOrder>();
+        let this: &dyn ScheduleLabel = &RecordingChecksum;
+        if !order.labels.iter().any(|label| **label == *this) {
+            order.insert_after(Last, RecordingChecksum);
+        }
+    }
+}
+
+/// Resource holding the recording of [`InputRecordPlugin`]
+pub struct InputRecorder {
+    pub path: PathBuf,
+    pub recording: InputRecording,
+    exit_reader: ManualEventReader<AppExit>,
+    saved: bool,
+}
+
+impl InputRecorder {
+    /// Writes the recording, logging the outcome
+    fn save(&mut self) {
+        self.saved = true;
+        match self.recording.save(&self.path) {
+            Ok(()) => info!(
+                "recorded {} frames of input to {}",
+                self.recording.frames.len(),
+                self.path.display()
+            ),
+            Err(e) => error!("{e}"),
+        }
+    }
+}
+
+impl Drop for InputRecorder {
+    fn drop(&mut self) {
+        if !self.saved && !self.recording.frames.is_empty() {
+            warn!("the app stopped without an `AppExit`, saving the recording without checksum");
+            self.save();
+        }
+    }
+}
+
+impl Plugin for InputRecordPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputRecorder {
+            path: self.path.clone(),
+            recording: InputRecording::new(),
+            exit_reader: ManualEventReader::default(),
+            saved: false,
+        })
+        .add_event::<WindowResized>()
+        .add_event::<WindowFocused>()
+        .add_event::<WindowCloseRequested>()
+        .init_resource::<WorldChecksum>()
+        .add_systems(PreUpdate, record_input_system)
+        .add_systems(RecordingChecksum, save_recording_system);
+        RecordingChecksum::add_to_main_order(app);
+    }
+
+    /// Warns if the [`WorldChecksum`] only hashes counts
+    fn cleanup(&self, app: &mut App) {
+        WorldChecksum::warn_if_only_counts(app, self.name());
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::of::<TimePlugin>(),
+            PluginDependency::of::<InputPlugin>(),
+        ]
+    }
+}
+
+/// Adds the input and window events of the update to the [`InputRecorder`]
+#[allow(clippy::too_many_arguments)]
+fn record_input_system(
+    mut recorder: ResMut<InputRecorder>,
+    time: Res<Time>,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    mut cursor: EventReader<CursorMoved>,
+    mut wheel: EventReader<MouseWheel>,
+    mut resized: EventReader<WindowResized>,
+    mut focused: EventReader<WindowFocused>,
+    mut close_requested: EventReader<WindowCloseRequested>,
+) {
+    let mut events: Vec<_> = keyboard
+        .read()
+        .copied()
+        .map(RecordedEvent::Keyboard)
+        .collect();
+    events.extend(
+        mouse_buttons
+            .read()
+            .copied()
+            .map(RecordedEvent::MouseButton),
+    );
+    events.extend(cursor.read().copied().map(RecordedEvent::CursorMoved));
+    events.extend(wheel.read().copied().map(RecordedEvent::MouseWheel));
+    events.extend(resized.read().copied().map(RecordedEvent::WindowResized));
+    events.extend(focused.read().copied().map(RecordedEvent::WindowFocused));
+    events.extend(
+        close_requested
+            .read()
+            .copied()
+            .map(RecordedEvent::WindowCloseRequested),
+    );
+    recorder.recording.frames.push(RecordedFrame {
+        delta: time.raw_delta(),
+        events,
+    });
+}
+
+/// Writes the recording with the checksum of the world once an [`AppExit`] is sent
+pub fn save_recording_system(world: &mut World) {
+    let exiting = {
+        let events = world.resource::<Events<AppExit>>();
+        let mut recorder = world.resource_mut::<InputRecorder>();
+        recorder.exit_reader.read(&events).count() > 0
+    };
+    if !exiting {
+        return;
+    }
+    let checksum = world.resource::<WorldChecksum>().checksum(world);
+    let mut recorder = world.resource_mut::<InputRecorder>();
+    recorder.recording.checksum = Some(checksum);
+    recorder.save();
+}
+
+/// Plays an [`InputRecording`] back, then exits with an error if the world checksum differs
+///
+/// Each recorded frame sends its input and window events and advances [`Time`] by its delta through
+/// [`TimeUpdateStrategy::ManualInstant`]. Events from the window still reach the app, so it
+/// should be left alone during a replay. The result is kept in the [`InputReplay`] resource.
+#[derive(Clone, Debug)]
+pub struct InputReplayPlugin {
+    pub path: PathBuf,
+}
+
+impl InputReplayPlugin {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// Resource holding the progress of [`InputReplayPlugin`]
+pub struct InputReplay {
+    pub recording: InputRecording,
+    frames_played: usize,
+    checksum: Option<u64>,
+}
+
+impl InputReplay {
+    pub fn frames_played(&self) -> usize {
+        self.frames_played
+    }
+
+    /// Whether every frame was played and the checksum computed
+    pub fn is_finished(&self) -> bool {
+        self.checksum.is_some()
+    }
+
+    /// Checksum of the world after the last frame, once finished
+    pub fn checksum(&self) -> Option<u64> {
+        self.checksum
+    }
+
+    /// Whether the checksum matches the recorded one, `None` until finished or without a
+    /// recorded checksum
+    pub fn checksum_matches(&self) -> Option<bool> {
+        Some(self.checksum? == self.recording.checksum?)
+    }
+}
+
+impl Plugin for InputReplayPlugin {
+    fn build(&self, app: &mut App) {
+        self.try_build(app).unwrap();
+    }
+
+    /// Fails if the recording cannot be read
+    fn try_build(&self, app: &mut App) -> Result<(), BoxedError> {
+        let recording = InputRecording::load(&self.path)?;
+        if recording.engine_version != env!("CARGO_PKG_VERSION") {
+            warn!(
+                "replaying input recorded with engine version {}",
+                recording.engine_version
+            );
+        }
+        app.insert_resource(InputReplay {
+            recording,
+            frames_played: 0,
+            checksum: None,
+        })
+        .add_event::<WindowResized>()
+        .add_event::<WindowFocused>()
+        .add_event::<WindowCloseRequested>()
+        .init_resource::<WorldChecksum>()
+        .add_systems(First, replay_input_system.before(time_system))
+        .add_systems(RecordingChecksum, finish_replay_system);
+        RecordingChecksum::add_to_main_order(app);
+        Ok(())
+    }
+
+    /// Warns if the [`WorldChecksum`] only hashes counts
+    fn cleanup(&self, app: &mut App) {
+        WorldChecksum::warn_if_only_counts(app, self.name());
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::of::<TimePlugin>(),
+            PluginDependency::of::<InputPlugin>(),
+        ]
+    }
+}
+
+/// Sends the events of the next recorded frame, and sets the time of the update
+pub fn replay_input_system(world: &mut World) {
+    let frame = {
+        let mut replay = world.resource_mut::<InputReplay>();
+        let frame = replay.recording.frames.get(replay.frames_played).cloned();
+        if frame.is_some() {
+            replay.frames_played += 1;
+        }
+        frame
+    };
+    let Some(frame) = frame else {
+        return;
+    };
+    let instant = {
+        let time = world.resource::<Time>();
+        time.last_update().unwrap_or(time.startup()) + frame.delta
+    };
+    world.insert_resource(TimeUpdateStrategy::ManualInstant(instant));
+    for event in frame.events {
+        match event {
+            RecordedEvent::Keyboard(event) => world.send_event(event),
+            RecordedEvent::MouseButton(event) => world.send_event(event),
+            RecordedEvent::CursorMoved(event) => world.send_event(event),
+            RecordedEvent::MouseWheel(event) => world.send_event(event),
+            RecordedEvent::WindowResized(event) => world.send_event(event),
+            RecordedEvent::WindowFocused(event) => world.send_event(event),
+            RecordedEvent::WindowCloseRequested(event) => world.send_event(event),
+        }
+    }
+}
+
+/// Verifies the checksum after the last frame, then sends an [`AppExit`]
+pub fn finish_replay_system(world: &mut World) {
+    {
+        let replay = world.resource::<InputReplay>();
+        if replay.is_finished() || replay.frames_played < replay.recording.frames.len() {
+            return;
+        }
+    }
+    let checksum = world.resource::<WorldChecksum>().checksum(world);
+    let exit = {
+        let mut replay = world.resource_mut::<InputReplay>();
+        replay.checksum = Some(checksum);
+        match replay.recording.checksum {
+            Some(expected) if expected == checksum => {
+                info!("replayed {} frames, checksum matches", replay.frames_played);
+                AppExit::Success
+            }
+            Some(expected) => {
+                error!(
+                    "replayed {} frames, checksum {checksum:#018x} differs from the recorded {expected:#018x}",
+                    replay.frames_played
+                );
+                AppExit::error()
+            }
+            None => {
+                warn!(
+                    "replayed {} frames, no checksum recorded",
+                    replay.frames_played
+                );
+                AppExit::Success
+            }
+        }
+    };
+    world.send_event(exit);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{hash::Hasher, time::Duration};
+
+    use crate::*;
+    use komorebi_app::prelude::*;
+    use komorebi_core::prelude::*;
+    use komorebi_ecs::prelude::*;
+    use komorebi_window::WindowResized;
+
+    /// Spawns an entity holding the elapsed milliseconds on every press of space
+    fn spawn_on_space(world: &mut World) {
+        if !world
+            .resource::<Input<KeyCode>>()
+            .just_pressed(KeyCode::Space)
+        {
+            return;
+        }
+        let elapsed = world.resource::<Time>().elapsed().as_millis() as u64;
+        let entity = world.spawn();
+        world.insert::<VecStorage<u64>>(entity, elapsed);
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .add_plugins(InputPlugin)
+            .insert_resource(WorldChecksum::new().with_storage::<VecStorage<u64>>("spawned"))
+            .add_systems(Update, spawn_on_space);
+        app.world.register::<VecStorage<u64>>();
+        app
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let dir = std::env::temp_dir().join(format!(
+            "komorebi_input_{}_record_and_replay",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("recording.ron");
+        let mut app = test_app();
+        app.add_plugins(InputRecordPlugin::new(&path))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                16,
+            )))
+            .step_n(2)
+            .send_event(KeyboardInput::pressed(KeyCode::Space))
+            .step_n(2)
+            .send_event(KeyboardInput::released(KeyCode::Space))
+            .send_event(WindowResized {
+                width: 800,
+                height: 600,
+            })
+            .step_n(1)
+            .send_event(KeyboardInput::pressed(KeyCode::Space))
+            .send_event(AppExit::Success)
+            .step_n(1);
+        let recording = InputRecording::load(&path).unwrap();
+        assert_eq!(recording.frames.len(), 6);
+        assert_eq!(recording.frames[5].delta, Duration::from_millis(16));
+        assert_eq!(
+            recording.frames[4].events,
+            [
+                RecordedEvent::Keyboard(KeyboardInput::released(KeyCode::Space)),
+                RecordedEvent::WindowResized(WindowResized {
+                    width: 800,
+                    height: 600
+                }),
+            ]
+        );
+        assert!(recording.checksum.is_some());
+
+        let mut replay = test_app();
+        replay.add_plugins(InputReplayPlugin::new(&path)).step_n(6);
+        assert_eq!(replay.should_exit(), Some(AppExit::Success));
+        assert_eq!(replay.world.entity_count(), 2);
+        let state = replay.world.resource::<InputReplay>();
+        assert_eq!(state.frames_played(), 6);
+        assert_eq!(state.checksum_matches(), Some(true));
+        drop(state);
+
+        // A different world is detected
+        let mut replay = test_app();
+        replay.add_plugins(InputReplayPlugin::new(&path)).step_n(3);
+        replay.world.spawn();
+        replay.step_n(3);
+        assert_eq!(replay.should_exit(), Some(AppExit::error()));
+
+        assert!(App::new()
+            .add_plugins(TimePlugin)
+            .add_plugins(InputPlugin)
+            .try_add_plugins(InputReplayPlugin::new(dir.join("missing.ron")))
+            .is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saved_when_stopped() {
+        let dir = std::env::temp_dir().join(format!(
+            "komorebi_input_{}_saved_when_stopped",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // An exit sent at the end of the update is seen
+        let exited = dir.join("exited.ron");
+        let mut app = test_app();
+        app.add_plugins(InputRecordPlugin::new(&exited))
+            .add_systems(Last, |world: &mut World| {
+                if world
+                    .resource::<Input<KeyCode>>()
+                    .just_pressed(KeyCode::Space)
+                {
+                    world.send_event(AppExit::Success);
+                }
+            })
+            .step_n(1)
+            .send_event(KeyboardInput::pressed(KeyCode::Space))
+            .step_n(1);
+        let checksum = app.world.resource::<WorldChecksum>().checksum(&app.world);
+        drop(app);
+        let recording = InputRecording::load(&exited).unwrap();
+        assert_eq!(recording.frames.len(), 2);
+        assert_eq!(recording.checksum, Some(checksum));
+
+        // A panic drops the app, which writes what was recorded
+        let panicked = dir.join("panicked.ron");
+        let mut app = test_app();
+        app.add_plugins(InputRecordPlugin::new(&panicked))
+            .add_systems(Update, |world: &mut World| {
+                if world
+                    .resource::<Input<KeyCode>>()
+                    .just_pressed(KeyCode::Space)
+                {
+                    panic!("space pressed");
+                }
+            });
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+            app.step_n(2)
+                .send_event(KeyboardInput::pressed(KeyCode::Space))
+                .step_n(2);
+        }));
+        assert!(result.is_err());
+        let recording = InputRecording::load(&panicked).unwrap();
+        assert_eq!(recording.frames.len(), 3);
+        assert_eq!(
+            recording.frames[2].events,
+            [RecordedEvent::Keyboard(KeyboardInput::pressed(
+                KeyCode::Space
+            ))]
+        );
+        assert_eq!(recording.checksum, None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn checksum_covers() {
+        assert!(WorldChecksum::new().hashes_only_counts());
+        let checksum = WorldChecksum::new().with_storage::<VecStorage<u64>>("values");
+        assert!(!checksum.hashes_only_counts());
+
+        let world_with = |value: u64| {
+            let mut world = World::new();
+            world.register::<VecStorage<u64>>();
+            let entity = world.spawn();
+            world.insert::<VecStorage<u64>>(entity, value);
+            world
+        };
+        let (one, two) = (world_with(1), world_with(2));
+        // Counts alone cannot tell the worlds apart
+        assert_eq!(
+            WorldChecksum::new().checksum(&one),
+            WorldChecksum::new().checksum(&two)
+        );
+        assert_ne!(checksum.checksum(&one), checksum.checksum(&two));
+        // Storages are told apart by their name
+        assert_ne!(
+            checksum.checksum(&one),
+            WorldChecksum::new()
+                .with_storage::<VecStorage<u64>>("other values")
+                .checksum(&one)
+        );
+    }
+
+    #[test]
+    fn stable_checksum() {
+        // FNV-1a of "a"
+        let mut hasher = ChecksumHasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        let mut world = World::new();
+        world.register::<VecStorage<u64>>();
+        let entity = world.spawn();
+        world.insert::<VecStorage<u64>>(entity, 7);
+        let checksum = WorldChecksum::new().with_storage::<VecStorage<u64>>("values");
+        assert_eq!(checksum.checksum(&world), checksum.checksum(&world));
+        let mut expected = ChecksumHasher::default();
+        expected.write_u64(1);
+        expected.write(b"values");
+        expected.write_u8(0xff);
+        expected.write_u8(1);
+        expected.write_u64(7);
+        expected.write_u64(1);
+        assert_eq!(checksum.checksum(&world), expected.finish());
+    }
+}
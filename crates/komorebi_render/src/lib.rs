@@ -1,17 +1,279 @@
 mod rendering;
+pub use rendering::{CameraInput, State};
 mod texture;
+pub use texture::Texture;
 
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use komorebi_app::{App, AppLabel, Plugin, SubApp};
+use komorebi_core::Time;
+use komorebi_ecs::{
+    IntoSystemConfigs, IntoSystemSetConfig, Res, ResMut, ScheduleLabel, SystemSet, World,
+};
+use komorebi_input::{Input, KeyCode};
+use komorebi_utils::tracing::warn;
+use komorebi_winit::WinitWindow;
+
+/// Label of the render [`SubApp`], whose world holds the GPU resources
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderApp;
+impl AppLabel for RenderApp {}
+
+/// Schedule run on the render world each frame, after extraction
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Render;
+impl ScheduleLabel for Render {}
+
+/// Schedule run on the render world to copy data from the [`MainWorld`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExtractSchedule;
+impl ScheduleLabel for ExtractSchedule {}
+
+/// The steps of the [`Render`] schedule, run in this order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderSet {
+    /// Create and update GPU resources from extracted data
+    Prepare,
+    /// Queue draws
+    Queue,
+    /// Submit the frame
+    Render,
+    /// Clear per-frame data
+    Cleanup,
+}
+impl SystemSet for RenderSet {}
+
+/// The main world, lent to the render world as a resource while [`ExtractSchedule`] runs
+pub struct MainWorld(World);
+
+impl Deref for MainWorld {
+    type Target = World;
+    fn deref(&self) -> &World {
+        &self.0
+    }
+}
+
+impl DerefMut for MainWorld {
+    fn deref_mut(&mut self) -> &mut World {
+        &mut self.0
+    }
+}
+
+/// Adds the render sub-app, which has its own world and runs [`ExtractSchedule`] then
+/// [`Render`] after each update of the main app
+///
+/// The render world creates its [`State`] once the main world has a [`WinitWindow`], then
+/// follows the window size, moves the camera with the [`CameraInput`] extracted from
+/// `Input<KeyCode>` by the extracted [`Time::delta`], and draws every frame.
+#[derive(Default)]
+pub struct RenderPlugin;
+
+impl Plugin for RenderPlugin {
+    fn build(&self, app: &mut App) {
+        let mut render_app = App::empty();
+        render_app
+            .set_main_schedule_label(Render)
+            .init_schedule(ExtractSchedule)
+            .configure_sets(
+                Render,
+                (
+                    RenderSet::Prepare,
+                    RenderSet::Queue.after(RenderSet::Prepare),
+                    RenderSet::Render.after(RenderSet::Queue),
+                    RenderSet::Cleanup.after(RenderSet::Render),
+                ),
+            )
+            .init_resource::<CameraInput>()
+            .add_systems(
+                ExtractSchedule,
+                (extract_window, extract_time, extract_camera_input),
+            )
+            .add_systems(
+                Render,
+                (
+                    prepare_state_system.in_set(RenderSet::Prepare),
+                    render_system.in_set(RenderSet::Render),
+                ),
+            );
+        app.insert_sub_app(RenderApp, SubApp::new(render_app, extract));
+    }
+}
+
+fn extract(main_world: &mut World, render_app: &mut App) {
+    let world = std::mem::take(main_world);
+    render_app.world.insert_resource(MainWorld(world));
+    render_app.world.run_schedule(ExtractSchedule);
+    let MainWorld(world) = render_app
+        .world
+        .remove_resource::<MainWorld>()
+        .expect("MainWorld was removed during extraction");
+    *main_world = world;
+}
+
+/// Creates the [`State`] once the windowing backend opened its window
+fn extract_window(world: &mut World) {
+    if world.contains_resource::<State>() {
+        return;
+    }
+    let window = {
+        let main_world = world.resource::<MainWorld>();
+        let window = main_world
+            .get_resource::<WinitWindow>()
+            .map(|window| Arc::clone(&window.0));
+        window
+    };
+    if let Some(window) = window {
+        let state = pollster::block_on(State::new(window));
+        world.insert_resource(state);
+    }
+}
+
+/// Copies the main world [`Time`], so that the camera moves by the delta of the update
+fn extract_time(world: &mut World) {
+    let time = world
+        .resource::<MainWorld>()
+        .get_resource::<Time>()
+        .map(|time| time.clone());
+    match time {
+        Some(time) => world.insert_resource(time),
+        None => {
+            world.remove_resource::<Time>();
+        }
+    }
+}
+
+/// Copies the keys moving the camera, W A S D or the arrows
+fn extract_camera_input(main_world: Res<MainWorld>, mut camera_input: ResMut<CameraInput>) {
+    let Some(keys) = main_world.get_resource::<Input<KeyCode>>() else {
+        return;
+    };
+    *camera_input = CameraInput {
+        forward: keys.any_pressed([KeyCode::W, KeyCode::Up]),
+        backward: keys.any_pressed([KeyCode::S, KeyCode::Down]),
+        left: keys.any_pressed([KeyCode::A, KeyCode::Left]),
+        right: keys.any_pressed([KeyCode::D, KeyCode::Right]),
+    };
+}
+
+/// Follows the window size and moves the camera with the extracted input
+fn prepare_state_system(
+    state: Option<ResMut<State>>,
+    camera_input: Res<CameraInput>,
+    time: Option<Res<Time>>,
+) {
+    let Some(mut state) = state else {
+        return;
+    };
+    let size = state.window().inner_size();
+    if size != state.size {
+        state.resize(size);
+    }
+    state.set_camera_input(*camera_input);
+    if let Some(time) = time {
+        state.update(time.delta());
+    }
+}
+
+fn render_system(state: Option<ResMut<State>>) {
+    let Some(mut state) = state else {
+        return;
+    };
+    // Nothing to draw to while minimized
+    if state.size.width == 0 || state.size.height == 0 {
+        return;
+    }
+    match state.render() {
+        Ok(()) => {}
+        // Reconfigure the surface if lost
+        Err(wgpu::SurfaceError::Lost) => {
+            let size = state.size;
+            state.resize(size);
+        }
+        Err(wgpu::SurfaceError::OutOfMemory) => panic!("the GPU is out of memory"),
+        // Outdated and Timeout are resolved by the next frame
+        Err(e) => warn!("cannot draw the frame: {e}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use komorebi_app::prelude::*;
+
+    #[derive(Default)]
+    struct Score(u32);
+
+    #[derive(Default)]
+    struct ExtractedScore(u32);
+
+    #[test]
+    fn extract_into_render_world() {
+        let mut app = App::new();
+        app.init_resource::<Score>()
+            .add_systems(Update, |mut score: ResMut<Score>| score.0 += 10)
+            .add_plugins(RenderPlugin);
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .init_resource::<ExtractedScore>()
+            .add_systems(
+                ExtractSchedule,
+                |main_world: Res<MainWorld>, mut extracted: ResMut<ExtractedScore>| {
+                    extracted.0 = main_world.resource::<Score>().0;
+                },
+            )
+            .add_systems(
+                Render,
+                (|mut extracted: ResMut<ExtractedScore>| extracted.0 += 1)
+                    .in_set(RenderSet::Prepare),
+            );
+
+        app.update();
+        app.update();
+        assert_eq!(app.world.resource::<Score>().0, 20);
+        assert_eq!(
+            app.sub_app(RenderApp).world.resource::<ExtractedScore>().0,
+            21
+        );
+        assert!(!app.world.contains_resource::<ExtractedScore>());
+    }
 
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn extract_camera_input() {
+        use komorebi_input::{InputPlugin, KeyboardInput};
+
+        let mut app = App::new();
+        app.add_plugins(InputPlugin)
+            .add_plugins(RenderPlugin)
+            .send_event(KeyboardInput::pressed(KeyCode::Up))
+            .step_n(1);
+        let input = *app.sub_app(RenderApp).world.resource::<CameraInput>();
+        assert_eq!(
+            input,
+            CameraInput {
+                forward: true,
+                ..Default::default()
+            }
+        );
+        assert!(!app
+            .sub_app(RenderApp)
+            .world
+            .contains_resource::<crate::State>());
+    }
+
+    #[test]
+    fn extract_time() {
+        use komorebi_core::TimePlugin;
+
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .add_plugins(RenderPlugin)
+            .step_n(2);
+        let delta = app.world.resource::<Time>().delta();
+        let render_time = app.sub_app(RenderApp).world.resource::<Time>();
+        assert_eq!(render_time.delta(), delta);
+        assert_eq!(render_time.frame_count(), 2);
     }
 }
@@ -61,6 +61,8 @@ impl Texture {
         bytes: &[u8],
         label: &str,
     ) -> Self {
+        #[cfg(feature = "trace")]
+        let _span = komorebi_utils::tracing::info_span!("texture load", label).entered();
         let img = image::load_from_memory(bytes).unwrap();
         Self::from_image(device, queue, &img, Some(label))
     }
@@ -1,4 +1,10 @@
+mod log;
+
+pub use log::*;
 pub use tracing;
 pub use tracing_subscriber;
 
-pub mod prelude {}
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::LogPlugin;
+}
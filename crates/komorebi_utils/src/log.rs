@@ -0,0 +1,288 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt, io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use tracing::Level;
+use tracing_subscriber::{
+    filter::{filter_fn, ParseError},
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+/// A layer added to the subscriber installed by [`LogPlugin`]
+pub type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync + 'static>;
+
+/// Installs the global `tracing` subscriber, printing to stdout and optionally to rolling files
+///
+/// The `RUST_LOG` environment variable overrides [`LogPlugin::level`] and
+/// [`LogPlugin::filter`]. Only the first subscriber of the process is installed; later ones
+/// are skipped with a warning.
+///
+/// The subscriber also keeps the last lines logged once [`keep_recent_log_lines`] is called.
+///
+/// With the `trace_chrome` feature, spans are also written to a Chrome trace file for
+/// `chrome://tracing` or Perfetto, `$TRACE_CHROME` or `trace-{timestamp}.json` in the working
+/// directory. The file is complete once the [`LogGuard`] is dropped, with the app.
+#[derive(Clone, Debug)]
+pub struct LogPlugin {
+    /// [`EnvFilter`] directives applied on top of `level`, like `wgpu=error,komorebi_app=debug`
+    pub filter: String,
+    /// Level of the events from targets `filter` does not mention
+    pub level: Level,
+    /// Print events as JSON lines instead of human readable text
+    pub json: bool,
+    /// Also write the events to files
+    pub file: Option<LogFile>,
+    /// Adds a layer to the subscriber, like an exporter to an external service
+    pub custom_layer: fn() -> Option<BoxedLayer>,
+}
+
+impl Default for LogPlugin {
+    fn default() -> Self {
+        Self {
+            filter: "wgpu=error,naga=warn".to_string(),
+            level: Level::INFO,
+            json: false,
+            file: None,
+            custom_layer: || None,
+        }
+    }
+}
+
+/// Log files written by [`LogPlugin`], named `{prefix}.{date}`
+#[derive(Clone, Debug)]
+pub struct LogFile {
+    pub directory: PathBuf,
+    pub prefix: String,
+    pub rotation: LogRotation,
+}
+
+impl LogFile {
+    pub fn new(directory: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+        Self {
+            directory: directory.into(),
+            prefix: prefix.into(),
+            rotation: LogRotation::Daily,
+        }
+    }
+}
+
+/// How often [`LogFile`]s start a new file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    /// Write a single file, named `{prefix}`
+    Never,
+}
+
+/// Keeps the outputs of [`LogPlugin`] open, flushing them when dropped
+#[derive(Default)]
+pub struct LogGuard {
+    #[cfg(feature = "trace_chrome")]
+    _chrome: Option<tracing_chrome::FlushGuard>,
+}
+
+/// Error returned by [`LogPlugin::install`]
+#[derive(Debug)]
+pub enum LogError {
+    /// The filter directives are invalid
+    Filter(ParseError),
+    /// The log file cannot be created
+    File { path: PathBuf, message: String },
+    /// A global subscriber was already installed
+    AlreadyInstalled,
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogError::Filter(e) => write!(f, "invalid log filter: {e}"),
+            LogError::File { path, message } => {
+                write!(f, "cannot create log file in {}: {message}", path.display())
+            }
+            LogError::AlreadyInstalled => write!(f, "a tracing subscriber is already installed"),
+        }
+    }
+}
+
+impl Error for LogError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LogError::Filter(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl LogPlugin {
+    /// The filter of the subscriber, from `RUST_LOG` or the settings
+    pub fn env_filter(&self) -> Result<EnvFilter, LogError> {
+        if let Ok(filter) = EnvFilter::try_from_default_env() {
+            return Ok(filter);
+        }
+        let directives = match self.filter.is_empty() {
+            true => self.level.to_string(),
+            false => format!("{},{}", self.level, self.filter),
+        };
+        EnvFilter::try_new(directives).map_err(LogError::Filter)
+    }
+
+    /// Installs the subscriber as the global default
+    ///
+    /// Fails without creating the log file or trace file when a global subscriber was already
+    /// installed.
+    pub fn install(&self) -> Result<LogGuard, LogError> {
+        let filter = self.env_filter()?;
+        if tracing::dispatcher::has_been_set() {
+            return Err(LogError::AlreadyInstalled);
+        }
+        #[allow(unused_mut)]
+        let mut guard = LogGuard::default();
+        let mut layers = vec![console_layer(self.json), recent_logs_layer()];
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(file) = &self.file {
+            layers.push(file_layer(file, self.json)?);
+        }
+        #[cfg(feature = "trace_chrome")]
+        {
+            let (layer, flush_guard) = chrome_layer();
+            layers.push(layer);
+            guard._chrome = Some(flush_guard);
+        }
+        layers.extend((self.custom_layer)());
+
+        tracing_subscriber::registry()
+            .with(layers)
+            .with(filter)
+            .try_init()
+            .map_err(|_| LogError::AlreadyInstalled)?;
+        Ok(guard)
+    }
+}
+
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static RECENT_LOGS_CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// Keeps the last `capacity` lines logged through the [`LogPlugin`] subscriber, none by default
+pub fn keep_recent_log_lines(capacity: usize) {
+    RECENT_LOGS_CAPACITY.store(capacity, Ordering::Relaxed);
+    let mut lines = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    while lines.len() > capacity {
+        lines.pop_front();
+    }
+}
+
+/// The last lines logged, oldest first, see [`keep_recent_log_lines`]
+pub fn recent_log_lines() -> Vec<String> {
+    let lines = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    lines.iter().cloned().collect()
+}
+
+/// Receives the events formatted by [`recent_logs_layer`]
+struct RecentLogsWriter;
+
+impl io::Write for RecentLogsWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let capacity = RECENT_LOGS_CAPACITY.load(Ordering::Relaxed);
+        if capacity == 0 {
+            return Ok(buf.len());
+        }
+        let mut lines = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+        for line in String::from_utf8_lossy(buf).lines() {
+            if lines.len() >= capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The layer keeping the last lines logged, skipping the formatting while none are kept
+fn recent_logs_layer() -> BoxedLayer {
+    Box::new(
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(|| RecentLogsWriter)
+            .with_filter(filter_fn(|_| {
+                RECENT_LOGS_CAPACITY.load(Ordering::Relaxed) > 0
+            })),
+    )
+}
+
+/// The layer printing to stdout
+#[cfg(not(target_arch = "wasm32"))]
+fn console_layer(json: bool) -> BoxedLayer {
+    match json {
+        true => Box::new(tracing_subscriber::fmt::layer().json()),
+        false => Box::new(tracing_subscriber::fmt::layer()),
+    }
+}
+
+/// The layer printing to the browser console, which has no JSON output
+#[cfg(target_arch = "wasm32")]
+fn console_layer(_json: bool) -> BoxedLayer {
+    Box::new(tracing_wasm::WASMLayer::new(
+        tracing_wasm::WASMLayerConfig::default(),
+    ))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn file_layer(file: &LogFile, json: bool) -> Result<BoxedLayer, LogError> {
+    use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+    let rotation = match file.rotation {
+        LogRotation::Minutely => Rotation::MINUTELY,
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+    let appender = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(&file.prefix)
+        .build(&file.directory)
+        .map_err(|e| LogError::File {
+            path: file.directory.clone(),
+            message: e.to_string(),
+        })?;
+    let layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(appender);
+    Ok(match json {
+        true => Box::new(layer.json()),
+        false => Box::new(layer),
+    })
+}
+
+#[cfg(feature = "trace_chrome")]
+fn chrome_layer() -> (BoxedLayer, tracing_chrome::FlushGuard) {
+    let path = std::env::var_os("TRACE_CHROME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros();
+            format!("trace-{timestamp}.json").into()
+        });
+    let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+        .file(path)
+        .include_args(true)
+        .build();
+    (Box::new(layer), guard)
+}
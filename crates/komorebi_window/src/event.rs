@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Event sent when the window is resized
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowResized {
+    /// Physical pixels
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Event sent when the window gains or loses focus
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowFocused {
+    pub focused: bool,
+}
+
+/// Event sent when closing the window is requested, e.g. with its close button
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowCloseRequested;
@@ -1,7 +1,22 @@
-pub mod prelude {}
+mod event;
 
-use komorebi_app::prelude::*;
+pub use event::*;
 
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{WindowCloseRequested, WindowFocused, WindowPlugin, WindowResized};
+}
+
+use komorebi_app::{prelude::*, BoxedError};
+use komorebi_config::{Settings, SettingsAppExt};
+use serde::{Deserialize, Serialize};
+
+/// Window settings, overridden by the `window` config section
+///
+/// The merged settings are inserted as a resource, updated when the config is reloaded. Also
+/// adds the window events, which the windowing backend sends.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WindowPlugin {
     pub close_when_requested: bool,
 }
@@ -14,8 +29,20 @@ impl Default for WindowPlugin {
     }
 }
 
+impl Settings for WindowPlugin {
+    const SECTION: &'static str = "window";
+}
+
 impl Plugin for WindowPlugin {
     fn build(&self, app: &mut App) {
-        todo!()
+        self.try_build(app).unwrap();
+    }
+
+    fn try_build(&self, app: &mut App) -> Result<(), BoxedError> {
+        app.add_event::<WindowResized>()
+            .add_event::<WindowFocused>()
+            .add_event::<WindowCloseRequested>()
+            .init_settings(self.clone())?;
+        Ok(())
     }
 }
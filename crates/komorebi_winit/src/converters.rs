@@ -0,0 +1,100 @@
+use komorebi_ecs::{Event, Events, World};
+use komorebi_input::{
+    ButtonState, CursorMoved, KeyCode, KeyboardInput, MouseButton, MouseButtonInput,
+    MouseScrollUnit, MouseWheel,
+};
+use komorebi_window::{WindowCloseRequested, WindowFocused, WindowResized};
+use winit::event::{self, MouseScrollDelta, VirtualKeyCode, WindowEvent};
+
+/// Sends the input or window event matching a winit window event, when the `InputPlugin` or
+/// the `WindowPlugin` added its events
+pub(crate) fn send_window_event(world: &mut World, event: &WindowEvent) {
+    match event {
+        WindowEvent::Resized(size) => send(
+            world,
+            WindowResized {
+                width: size.width,
+                height: size.height,
+            },
+        ),
+        WindowEvent::Focused(focused) => send(world, WindowFocused { focused: *focused }),
+        WindowEvent::CloseRequested => send(world, WindowCloseRequested),
+        WindowEvent::KeyboardInput { input, .. } => send(
+            world,
+            KeyboardInput {
+                scan_code: input.scancode,
+                key_code: input.virtual_keycode.and_then(convert_key_code),
+                state: convert_element_state(input.state),
+            },
+        ),
+        WindowEvent::MouseInput { state, button, .. } => send(
+            world,
+            MouseButtonInput {
+                button: convert_mouse_button(*button),
+                state: convert_element_state(*state),
+            },
+        ),
+        WindowEvent::CursorMoved { position, .. } => send(
+            world,
+            CursorMoved {
+                position: [position.x as f32, position.y as f32],
+            },
+        ),
+        WindowEvent::MouseWheel { delta, .. } => send(
+            world,
+            match *delta {
+                MouseScrollDelta::LineDelta(x, y) => MouseWheel {
+                    unit: MouseScrollUnit::Line,
+                    x,
+                    y,
+                },
+                MouseScrollDelta::PixelDelta(position) => MouseWheel {
+                    unit: MouseScrollUnit::Pixel,
+                    x: position.x as f32,
+                    y: position.y as f32,
+                },
+            },
+        ),
+        _ => {}
+    }
+}
+
+fn send<E: Event>(world: &mut World, event: E) {
+    if let Some(mut events) = world.get_resource_mut::<Events<E>>() {
+        events.send(event);
+    }
+}
+
+fn convert_element_state(state: event::ElementState) -> ButtonState {
+    match state {
+        event::ElementState::Pressed => ButtonState::Pressed,
+        event::ElementState::Released => ButtonState::Released,
+    }
+}
+
+fn convert_mouse_button(button: event::MouseButton) -> MouseButton {
+    match button {
+        event::MouseButton::Left => MouseButton::Left,
+        event::MouseButton::Right => MouseButton::Right,
+        event::MouseButton::Middle => MouseButton::Middle,
+        event::MouseButton::Other(other) => MouseButton::Other(other),
+    }
+}
+
+fn convert_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
+    macro_rules! same_names {
+        ($($key:ident),* $(,)?) => {
+            match key_code {
+                $(VirtualKeyCode::$key => Some(KeyCode::$key),)*
+                _ => None,
+            }
+        };
+    }
+    same_names!(
+        Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0, A, B, C, D, E, F, G, H, I, J,
+        K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9,
+        F10, F11, F12, Insert, Home, Delete, End, PageDown, PageUp, Left, Up, Right, Down, Back,
+        Return, Space, Tab, Minus, Equals, Comma, Period, Slash, Semicolon, Apostrophe, Grave,
+        LBracket, RBracket, Backslash, LAlt, RAlt, LControl, RControl, LShift, RShift, LWin, RWin,
+    )
+}
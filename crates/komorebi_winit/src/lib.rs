@@ -1,33 +1,91 @@
+mod converters;
 mod window;
 mod winit_windows;
 
+use std::sync::Arc;
+
 use komorebi_app::prelude::*;
+use komorebi_window::WindowPlugin;
 use winit::{
     event::{Event, WindowEvent},
-    event_loop::{ControlFlow, EventLoopBuilder, EventLoopWindowTarget},
+    event_loop::{ControlFlow, EventLoopBuilder},
 };
 
 #[derive(Default)]
 pub struct WinitPlugin;
 
+/// Resource with the window opened by [`winit_runner`], inserted before the first update
+pub struct WinitWindow(pub Arc<winit::window::Window>);
+
 impl Plugin for WinitPlugin {
     fn build(&self, app: &mut App) {
-        let event_handler = move |event: Event<()>,
-                                  event_loop: &EventLoopWindowTarget<()>,
-                                  control_flow: &mut ControlFlow| {
-            match event {
-                Event::WindowEvent { window_id, event } => match event {
-                    WindowEvent::CloseRequested => {
-                        *control_flow = ControlFlow::Exit;
-                    }
-                    _ => {}
-                },
-                _ => {}
+        app.add_event::<AppExit>().set_runner(winit_runner);
+    }
+}
+
+/// Runs the winit event loop, updating the app every time the pending events are handled
+///
+/// Window events are sent as the events of the `InputPlugin` and the [`WindowPlugin`]. Closing the window sends
+/// [`AppExit::Success`] unless disabled by [`WindowPlugin::close_when_requested`], and the
+/// loop stops once an [`AppExit`] event is sent. On the web and iOS, where the event loop
+/// cannot return, the process exits instead.
+pub fn winit_runner(mut app: App) -> AppExit {
+    window::init_platform();
+    let event_loop = EventLoopBuilder::<()>::with_user_event().build();
+    let window = Arc::new(winit_windows::WinitWindows::create_window(&event_loop));
+    #[cfg(target_arch = "wasm32")]
+    window::attach_canvas(&window);
+    app.world.insert_resource(WinitWindow(Arc::clone(&window)));
+
+    let mut handle_event = move |event: Event<()>, control_flow: &mut ControlFlow| {
+        *control_flow = ControlFlow::Poll;
+        match event {
+            Event::WindowEvent {
+                window_id,
+                event: WindowEvent::CloseRequested,
+            } if window_id == window.id() => {
+                converters::send_window_event(&mut app.world, &WindowEvent::CloseRequested);
+                let close = app
+                    .world
+                    .get_resource::<WindowPlugin>()
+                    .map_or(true, |settings| settings.close_when_requested);
+                if close {
+                    app.world.send_event(AppExit::Success);
+                }
+            }
+            Event::WindowEvent { window_id, event } if window_id == window.id() => {
+                converters::send_window_event(&mut app.world, &event);
+            }
+            Event::MainEventsCleared => {
+                // Plugins may become ready later on the web, where `App::run` cannot wait
+                if app.finish_plugins_if_ready() {
+                    app.update();
+                }
+                if let Some(exit) = app.should_exit() {
+                    *control_flow = ControlFlow::ExitWithCode(exit.code().into());
+                    return Some(exit);
+                }
             }
-        };
+            _ => {}
+        }
+        None
+    };
 
-        let event_loop = EventLoopBuilder::<()>::with_user_event().build();
-        let window = winit_windows::WinitWindows::create_window(&event_loop);
-        event_loop.run(event_handler);
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_arch = "wasm32", target_os = "ios"))] {
+            event_loop.run(move |event, _, control_flow| {
+                handle_event(event, control_flow);
+            })
+        } else {
+            use winit::platform::run_return::EventLoopExtRunReturn;
+            let mut event_loop = event_loop;
+            let mut exit = AppExit::Success;
+            event_loop.run_return(|event, _, control_flow| {
+                if let Some(app_exit) = handle_event(event, control_flow) {
+                    exit = app_exit;
+                }
+            });
+            exit
+        }
     }
 }
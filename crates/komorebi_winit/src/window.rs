@@ -1,109 +1,23 @@
-// use crate::rendering;
-use komorebi_utils::tracing::{info, warn};
-use komorebi_utils::tracing_subscriber;
-use winit::{
-    event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
-};
-
-pub struct Window {}
-
-impl Window {
-    pub fn new() -> Self {
-        Self {}
-    }
-
-    pub fn run(&self) {
-        pollster::block_on(self.run_async());
-    }
-
-    pub async fn run_async(&self) {
-        cfg_if::cfg_if! {
-            if #[cfg(target_arch = "wasm32")] {
-                console_error_panic_hook::set_once();
-                tracing_wasm::set_as_global_default();
-                warn!("wasm32 works!");
-                // panic!("test panic!");
-            } else {
-                tracing_subscriber::fmt::init();
-            }
-        }
-        info!("info!!");
-        let event_loop = EventLoop::new();
-        let window = WindowBuilder::new().build(&event_loop).unwrap();
-        #[cfg(target_arch = "wasm32")]
-        {
-            // Winit prevents sizing with CSS, so we have to set
-            // the size manually when on web.
-            use winit::dpi::PhysicalSize;
-            window.set_inner_size(PhysicalSize::new(600, 400));
-            warn!("set window size!");
-
-            use winit::platform::web::WindowExtWebSys;
-            web_sys::window()
-                .and_then(|win| win.document())
-                .and_then(|doc| {
-                    let dst = doc.get_element_by_id("wasm-example")?;
-                    let canvas = web_sys::Element::from(window.canvas());
-                    dst.append_child(&canvas).ok()?;
-                    Some(())
-                })
-                .expect("Couldn't append canvas to document body.");
-        }
-
-        // let mut state = rendering::State::new(&window).await;
-
-        event_loop.run(move |event, _, control_flow| {
-            *control_flow = ControlFlow::Wait;
+/// Sets up panic reporting for the platform, logging is set up by `LogPlugin`
+pub(crate) fn init_platform() {
+    #[cfg(target_arch = "wasm32")]
+    console_error_panic_hook::set_once();
+}
 
-            match event {
-                Event::WindowEvent {
-                    ref event,
-                    window_id,
-                } if window_id == window.id() => {
-                    // if !state.input(event) {
-                    //     match event {
-                    //         WindowEvent::CloseRequested
-                    //         | WindowEvent::KeyboardInput {
-                    //             input:
-                    //                 KeyboardInput {
-                    //                     state: ElementState::Pressed,
-                    //                     virtual_keycode: Some(VirtualKeyCode::Escape),
-                    //                     ..
-                    //                 },
-                    //             ..
-                    //         } => *control_flow = ControlFlow::Exit,
-                    //         WindowEvent::Resized(physical_size) => {
-                    //             state.resize(*physical_size);
-                    //         }
-                    //         WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                    //             // new_inner_size is &&mut so we have to dereference it twice
-                    //             state.resize(**new_inner_size);
-                    //         }
-                    //         _ => (),
-                    //     }
-                    // }
-                }
-                // Event::RedrawRequested(window_id) if window_id == window.id() => {
-                //     state.update();
-                //     match state.render() {
-                //         Ok(_) => {}
-                //         // Reconfigure the surface if lost
-                //         Err(wgpu::SurfaceError::Lost) => state.resize(state.size),
-                //         // The system is out of memory, we should probably quit
-                //         Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
-                //         // All other errors (Outdated, Timeout) should be resolved by the next frame
-                //         Err(e) => eprintln!("{:?}", e),
-                //     }
-                // }
-                // Event::MainEventsCleared => {
-                //     // RedrawRequested will only trigger once, unless we manually
-                //     // request it.
-                //     window.request_redraw();
-                // }
-                _ => (),
-            }
-        });
-    }
+/// Puts the window canvas in the page on the web, where winit cannot size it with CSS
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn attach_canvas(window: &winit::window::Window) {
+    use winit::dpi::PhysicalSize;
+    window.set_inner_size(PhysicalSize::new(600, 400));
+
+    use winit::platform::web::WindowExtWebSys;
+    web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| {
+            let dst = doc.get_element_by_id("wasm-example")?;
+            let canvas = web_sys::Element::from(window.canvas());
+            dst.append_child(&canvas).ok()?;
+            Some(())
+        })
+        .expect("Couldn't append canvas to document body.");
 }
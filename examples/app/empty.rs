@@ -1,5 +1,5 @@
 use komorebi::prelude::*;
 
-fn main() {
-    App::new().run();
+fn main() -> AppExit {
+    App::new().run()
 }
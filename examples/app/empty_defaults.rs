@@ -2,6 +2,6 @@
 
 use komorebi::prelude::*;
 
-fn main() {
-    App::new().add_plugins(DefaultPlugins).run();
+fn main() -> AppExit {
+    App::new().add_plugins(DefaultPlugins).run()
 }
@@ -6,8 +6,31 @@ impl PluginGroup for DefaultPlugins {
     fn build(self) -> PluginGroupBuilder {
         let mut group = PluginGroupBuilder::start::<Self>();
         group = group
-            // .add(komorebi_window::WindowPlugin::default())
-            .add(komorebi_winit::WinitPlugin::default());
+            .add(komorebi_utils::LogPlugin::default())
+            .add(komorebi_app::PanicHandlerPlugin {
+                engine_version: env!("CARGO_PKG_VERSION"),
+                ..Default::default()
+            })
+            .add(komorebi_config::ConfigPlugin::default())
+            .add(komorebi_core::TaskPoolPlugin::default())
+            .add(komorebi_core::TimePlugin)
+            .add(komorebi_input::InputPlugin)
+            .add(komorebi_window::WindowPlugin::default())
+            .add(komorebi_winit::WinitPlugin)
+            .add(komorebi_render::RenderPlugin);
         group
     }
 }
+
+/// The plugins to run an app headless, like in tests driving it with
+/// [`App::step_n`](komorebi_app::App::step_n)
+pub struct MinimalPlugins;
+
+impl PluginGroup for MinimalPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(komorebi_core::TaskPoolPlugin::default())
+            .add(komorebi_core::TimePlugin)
+            .add(komorebi_app::ScheduleRunnerPlugin::default())
+    }
+}
@@ -8,14 +8,35 @@ pub mod app {
     pub use komorebi_app::*;
 }
 
+pub mod config {
+    pub use komorebi_config::*;
+}
+
 pub mod core {
     pub use komorebi_core::*;
 }
 
+pub mod diagnostic {
+    pub use komorebi_diagnostic::*;
+}
+
+#[cfg(feature = "dynamic_plugin")]
+pub mod dynamic_plugin {
+    pub use komorebi_dynamic_plugin::*;
+}
+
 pub mod ecs {
     pub use komorebi_ecs::*;
 }
 
+pub mod input {
+    pub use komorebi_input::*;
+}
+
+pub mod render {
+    pub use komorebi_render::*;
+}
+
 pub mod utils {
     pub use komorebi_utils::*;
 }
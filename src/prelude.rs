@@ -1,5 +1,5 @@
 #[doc(hidden)]
 pub use crate::{
-    app::prelude::*, core::prelude::*, ecs::prelude::*, utils::prelude::*, window::prelude::*,
-    DefaultPlugins,
+    app::prelude::*, config::prelude::*, core::prelude::*, diagnostic::prelude::*, ecs::prelude::*,
+    input::prelude::*, utils::prelude::*, window::prelude::*, DefaultPlugins, MinimalPlugins,
 };